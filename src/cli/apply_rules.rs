@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+
+use crate::{
+    config::WindowRule,
+    label::{space::StableSpaceIndex, Labelable},
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::{QuerySpaces, QueryWindows, ToggleWindow, WindowToggle},
+        transport::{Space, Window},
+    },
+};
+
+use super::move_window_to_space::send_window_to_stable_index;
+
+/// Moves every window matching a rule to the space assigned by the rule, creating the space when
+/// needed, and applies the rule's float and sticky state.
+///
+/// Failures for single windows are logged, so the remaining windows are still handled.
+pub fn apply_window_rules(rules: &[WindowRule]) -> anyhow::Result<()> {
+    if rules.is_empty() {
+        log::warn!("No window rules are configured");
+        return Ok(());
+    }
+
+    let windows = execute_yabai_cmd(&QueryWindows)
+        .context("Could not query windows")?
+        .context("Could not parse windows")?;
+
+    let spaces = execute_yabai_cmd(&QuerySpaces {
+        only_current_display: false,
+    })
+    .context("Could not query spaces")?
+    .context("Could not parse spaces")?;
+
+    let stable_indexes: HashMap<_, _> = spaces
+        .iter()
+        .filter_map(|space| {
+            let stable_index = Space::parse_index(space.label.as_deref()?).ok()?;
+            Some((space.index.0, stable_index))
+        })
+        .collect();
+
+    for window in windows.iter().filter(|window| !window.is_native_fullscreen) {
+        let Some(rule) = find_rule(rules, window) else {
+            continue;
+        };
+
+        if let Err(error) = apply_rule(rule, window, &stable_indexes) {
+            log::warn!("Cannot apply a rule to window {:?}: {error:?}", window.id);
+        }
+    }
+
+    Ok(())
+}
+
+fn apply_rule(
+    rule: &WindowRule,
+    window: &Window,
+    stable_indexes: &HashMap<u32, StableSpaceIndex>,
+) -> anyhow::Result<()> {
+    for toggle in get_toggles(rule, window) {
+        log::info!("Toggling {toggle} for window {:?}", window.id);
+        execute_yabai_cmd(&ToggleWindow {
+            window_id: Some(window.id),
+            toggle,
+        })
+        .with_context(|| format!("Cannot toggle {toggle} for window {:?}", window.id))?;
+    }
+
+    if stable_indexes.get(&window.space_index.0) == Some(&rule.space) {
+        log::debug!(
+            "Window {:?} is already in the space with stable index {:?}",
+            window.id,
+            rule.space
+        );
+        return Ok(());
+    }
+
+    send_window_to_stable_index(window.id, rule.space)
+}
+
+fn find_rule<'r>(rules: &'r [WindowRule], window: &Window) -> Option<&'r WindowRule> {
+    rules.iter().find(|rule| rule.criteria().matches(window))
+}
+
+fn get_toggles(rule: &WindowRule, window: &Window) -> Vec<WindowToggle> {
+    [
+        (rule.float, window.is_floating, WindowToggle::Float),
+        (rule.sticky, window.is_sticky, WindowToggle::Sticky),
+    ]
+    .into_iter()
+    .filter(|(desired, current, _)| desired.is_some_and(|desired| desired != *current))
+    .map(|(_, _, toggle)| toggle)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::yabai::transport::{fixtures, Frame};
+
+    fn rules() -> Vec<WindowRule> {
+        toml::from_str::<Config>(
+            r#"
+            [[rules]]
+            app = "Slack"
+            title-regex = "Huddle"
+            space = 2
+            float = true
+
+            [[rules]]
+            app = "Slack"
+            space = 3
+            sticky = false
+            "#,
+        )
+        .unwrap()
+        .rules
+    }
+
+    fn window(app: &str, title: &str) -> Window {
+        Window {
+            app: app.to_owned(),
+            title: title.to_owned(),
+            ..fixtures::window(1, Frame::default())
+        }
+    }
+
+    #[test]
+    fn finds_first_matching_rule() {
+        let rules = rules();
+
+        let rule = find_rule(&rules, &window("Slack", "Huddle: general")).unwrap();
+        assert_eq!(2, *rule.space);
+
+        let rule = find_rule(&rules, &window("slack", "general")).unwrap();
+        assert_eq!(3, *rule.space);
+
+        assert!(find_rule(&rules, &window("Firefox", "Huddle")).is_none());
+    }
+
+    #[test]
+    fn toggles_only_differing_properties() {
+        let rules = rules();
+        let huddle = window("Slack", "Huddle");
+
+        assert_eq!(vec![WindowToggle::Float], get_toggles(&rules[0], &huddle));
+        assert!(get_toggles(&rules[1], &huddle).is_empty());
+
+        let sticky = Window {
+            is_sticky: true,
+            ..window("Slack", "general")
+        };
+        assert_eq!(vec![WindowToggle::Sticky], get_toggles(&rules[1], &sticky));
+    }
+}
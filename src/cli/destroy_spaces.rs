@@ -1,20 +1,63 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
 use anyhow::Context;
 use itertools::Itertools;
 
-use crate::yabai::{
-    cli::execute_yabai_cmd,
-    command::{DestoySpace, QuerySpaces},
-    transport::Space,
+use crate::{
+    config::DestroySpacesConfig,
+    label::Labelable,
+    state::State,
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::{DestoySpace, QuerySpaces, QueryWindows},
+        transport::{Space, WindowId},
+    },
 };
 
-pub fn destroy_empty_background_spaces() -> anyhow::Result<()> {
-    let spaces = execute_yabai_cmd(&QuerySpaces {
-        only_current_display: false,
-    })
-    .context("Cannot query yabai spaces")?
-    .context("Cannot parse spaces")?;
+/// Marks the currently visible spaces as seen now. Call it before switching spaces, so the spaces
+/// that become hidden count as visible until the switch.
+///
+/// Does nothing when there is no `unfocused-timeout`.
+pub fn record_visible_spaces(config: &DestroySpacesConfig) -> anyhow::Result<()> {
+    if config.unfocused_timeout.is_none() {
+        return Ok(());
+    }
+
+    let spaces = query_spaces()?;
+    update_spaces_last_visible(&spaces, unix_now()?)?;
 
-    let spaces_to_remove = spaces.into_iter().filter(space_destoyable).collect_vec();
+    Ok(())
+}
+
+pub fn destroy_empty_background_spaces(config: &DestroySpacesConfig) -> anyhow::Result<()> {
+    let spaces = query_spaces()?;
+
+    let hidden_windows = if config.ignore_hidden_windows {
+        execute_yabai_cmd(&QueryWindows)
+            .context("Cannot query yabai windows")?
+            .context("Cannot parse windows")?
+            .into_iter()
+            .filter(|window| window.is_minimized || window.is_hidden)
+            .map(|window| window.id)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
+    let now = unix_now()?;
+    // NOTE: when spaces were last visible only matters with a timeout, so the state is left alone
+    // otherwise
+    let spaces_last_visible = if config.unfocused_timeout.is_some() {
+        update_spaces_last_visible(&spaces, now)?
+    } else {
+        BTreeMap::new()
+    };
+
+    let spaces_to_remove =
+        select_spaces_to_destroy(&spaces, &hidden_windows, &spaces_last_visible, now, config);
 
     log::info!("Will destroy {} spaces", spaces_to_remove.len());
 
@@ -40,6 +83,197 @@ pub fn destroy_empty_background_spaces() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn space_destoyable(space: &Space) -> bool {
-    space.windows.is_empty() && !space.is_visible
+fn query_spaces() -> anyhow::Result<Vec<Space>> {
+    execute_yabai_cmd(&QuerySpaces {
+        only_current_display: false,
+    })
+    .context("Cannot query yabai spaces")?
+    .context("Cannot parse spaces")
+}
+
+fn unix_now() -> anyhow::Result<u64> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("The system time is before the UNIX epoch")?
+        .as_secs())
+}
+
+fn update_spaces_last_visible(spaces: &[Space], now: u64) -> anyhow::Result<BTreeMap<String, u64>> {
+    State::update(|state| {
+        update_last_visible(&mut state.spaces_last_visible, spaces, now);
+        state.spaces_last_visible.clone()
+    })
+    .context("Cannot update when spaces were last visible")
+}
+
+/// Marks visible spaces as seen at `now`. Spaces seen for the first time count as visible now, so
+/// they are not destroyed before the unfocused timeout passes.
+fn update_last_visible(last_visible: &mut BTreeMap<String, u64>, spaces: &[Space], now: u64) {
+    last_visible.retain(|uuid, _| spaces.iter().any(|space| &space.uuid == uuid));
+
+    for space in spaces {
+        let seen_at = last_visible.entry(space.uuid.clone()).or_insert(now);
+        if space.is_visible {
+            *seen_at = now;
+        }
+    }
+}
+
+fn select_spaces_to_destroy<'s>(
+    spaces: &'s [Space],
+    hidden_windows: &HashSet<WindowId>,
+    spaces_last_visible: &BTreeMap<String, u64>,
+    now: u64,
+    config: &DestroySpacesConfig,
+) -> Vec<&'s Space> {
+    let mut spaces_per_display = spaces.iter().counts_by(|space| *space.display_index);
+
+    spaces
+        .iter()
+        .filter(|space| space_destoyable(space, hidden_windows))
+        .filter(|space| !(config.keep_described && space.label_description().is_some()))
+        .filter(|space| {
+            space
+                .label
+                .as_deref()
+                .and_then(|label| Space::parse_index(label).ok())
+                .is_none_or(|stable_index| !config.keep_indexes.contains(&stable_index))
+        })
+        .filter(|space| {
+            config.unfocused_timeout.is_none_or(|timeout| {
+                spaces_last_visible
+                    .get(&space.uuid)
+                    .is_some_and(|last_visible| now.saturating_sub(*last_visible) >= timeout)
+            })
+        })
+        // NOTE: keep the first spaces of a display when enforcing the minimum
+        .sorted_by_key(|space| std::cmp::Reverse(space.index))
+        .filter(|space| {
+            let display_spaces = spaces_per_display
+                .get_mut(&*space.display_index)
+                .expect("Every space is counted");
+
+            let destroy = *display_spaces > config.min_spaces_per_display;
+            if destroy {
+                *display_spaces -= 1;
+            }
+            destroy
+        })
+        .collect()
+}
+
+fn space_destoyable(space: &Space, hidden_windows: &HashSet<WindowId>) -> bool {
+    // NOTE: native fullscreen spaces are destroyed by macOS when the window leaves fullscreen
+    space
+        .windows
+        .iter()
+        .all(|window_id| hidden_windows.contains(window_id))
+        && !space.is_visible
+        && !space.is_native_fullscreen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yabai::transport::{fixtures, DisplayIndex};
+
+    fn space(index: u32, label: Option<&str>, display_index: u32) -> Space {
+        Space {
+            display_index: DisplayIndex(display_index),
+            ..fixtures::space(index, label)
+        }
+    }
+
+    fn select(spaces: &[Space], config: &DestroySpacesConfig) -> Vec<u32> {
+        let last_visible = spaces
+            .iter()
+            .map(|space| (space.uuid.clone(), 100 - 10 * space.index.0 as u64))
+            .collect();
+
+        select_spaces_to_destroy(spaces, &HashSet::new(), &last_visible, 100, config)
+            .into_iter()
+            .map(|space| space.index.0)
+            .sorted()
+            .collect()
+    }
+
+    fn spaces() -> Vec<Space> {
+        vec![
+            Space {
+                is_visible: true,
+                ..space(1, Some("1:"), 1)
+            },
+            space(2, Some("2: Music"), 1),
+            space(3, Some("3:"), 1),
+            space(4, None, 1),
+            Space {
+                windows: vec![WindowId(1)],
+                ..space(5, Some("4:"), 2)
+            },
+            space(6, Some("5:"), 2),
+        ]
+    }
+
+    #[test]
+    fn destroys_empty_background_spaces_by_default() {
+        assert_eq!(
+            vec![2, 3, 4, 6],
+            select(&spaces(), &DestroySpacesConfig::default())
+        );
+    }
+
+    #[test]
+    fn keeps_spaces_matching_policies() {
+        let config = |toml| toml::from_str::<DestroySpacesConfig>(toml).unwrap();
+
+        assert_eq!(
+            vec![3, 4, 6],
+            select(&spaces(), &config("keep-described = true"))
+        );
+        assert_eq!(
+            vec![2, 4],
+            select(&spaces(), &config("keep-indexes = [3, 5]"))
+        );
+        assert_eq!(
+            vec![4],
+            select(&spaces(), &config("min-spaces-per-display = 3"))
+        );
+        assert_eq!(
+            vec![3, 4, 6],
+            select(&spaces(), &config("unfocused-timeout = 30"))
+        );
+    }
+
+    #[test]
+    fn ignores_hidden_windows() {
+        let spaces = spaces();
+        let hidden_windows = HashSet::from([WindowId(1)]);
+
+        let selected = select_spaces_to_destroy(
+            &spaces,
+            &hidden_windows,
+            &BTreeMap::new(),
+            0,
+            &DestroySpacesConfig::default(),
+        );
+
+        assert!(selected.iter().any(|space| space.index.0 == 5));
+    }
+
+    #[test]
+    fn updates_when_spaces_were_last_visible() {
+        let spaces = spaces();
+        let mut last_visible = BTreeMap::from([
+            ("space-1".to_owned(), 10),
+            ("space-2".to_owned(), 10),
+            ("removed".to_owned(), 10),
+        ]);
+
+        update_last_visible(&mut last_visible, &spaces, 50);
+
+        assert_eq!(Some(&50), last_visible.get("space-1"));
+        assert_eq!(Some(&10), last_visible.get("space-2"));
+        assert_eq!(Some(&50), last_visible.get("space-3"));
+        assert_eq!(None, last_visible.get("removed"));
+    }
 }
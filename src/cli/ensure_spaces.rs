@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use log::info;
+
+use crate::{
+    config::Config,
+    label::{
+        space::{create_space_with_label, StableSpaceIndex},
+        Labelable,
+    },
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::LabelSpace,
+        transport::{Space, SpaceIndex},
+    },
+};
+
+use super::rebalance::query_spaces_and_displays;
+
+#[derive(Debug, PartialEq, Eq)]
+enum SpaceChange<'c> {
+    /// Adds the configured description to an existing space without one.
+    Describe { index: SpaceIndex, label: String },
+    Create {
+        label: String,
+        display_uuid: Option<&'c str>,
+    },
+}
+
+/// Creates the missing spaces with stable indexes from 1 to `count`, or with the stable indexes
+/// in the `spaces` config section when `count` is `None`.
+///
+/// Spaces are created on their assigned display, or on the active display when they have none or
+/// it is not connected. Spaces get the description from the config.
+pub fn ensure_spaces(count: Option<StableSpaceIndex>, config: &Config) -> anyhow::Result<()> {
+    let stable_indexes: Vec<_> = match count {
+        Some(count) => (1..=*count)
+            .map(|index| {
+                StableSpaceIndex::try_from(index).expect("Indexes up to a stable index are valid")
+            })
+            .collect(),
+        None => config.spaces.keys().copied().collect(),
+    };
+
+    let (spaces, displays) = query_spaces_and_displays()?;
+    let changes = plan_changes(&stable_indexes, &spaces, config);
+
+    info!("Will create or describe {} spaces", changes.len());
+
+    // NOTE: creating spaces changes the indexes of spaces on other displays, so existing spaces
+    // are labeled first
+    let (describe, create): (Vec<_>, Vec<_>) = changes
+        .into_iter()
+        .partition(|change| matches!(change, SpaceChange::Describe { .. }));
+
+    for change in describe.into_iter().chain(create) {
+        match change {
+            SpaceChange::Describe { index, label } => {
+                execute_yabai_cmd(&LabelSpace::new(index, label.clone()))
+                    .with_context(|| format!("Cannot set label {label} to space {index:?}"))?;
+            }
+            SpaceChange::Create {
+                label,
+                display_uuid,
+            } => {
+                let display_index = display_uuid.and_then(|display_uuid| {
+                    let display = displays.iter().find(|display| display.uuid.eq_ignore_ascii_case(display_uuid));
+                    if display.is_none() {
+                        info!("Display {display_uuid} assigned to space {label:?} is not connected. Creating the space on the active display");
+                    }
+
+                    display.map(|display| display.index)
+                });
+
+                create_space_with_label(label.clone(), display_index)
+                    .with_context(|| format!("Cannot create space with label {label}"))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn plan_changes<'c>(
+    stable_indexes: &[StableSpaceIndex],
+    spaces: &[Space],
+    config: &'c Config,
+) -> Vec<SpaceChange<'c>> {
+    let existing_spaces: BTreeMap<_, _> = spaces
+        .iter()
+        .filter_map(|space| Some((Space::parse_index(space.label.as_deref()?).ok()?, space)))
+        .collect();
+
+    stable_indexes
+        .iter()
+        .filter_map(|&stable_index| {
+            let description = config.space_description(stable_index);
+            let label = Space::label(stable_index, description);
+
+            match existing_spaces.get(&stable_index) {
+                Some(space) => (description.is_some() && space.label_description().is_none())
+                    .then_some(SpaceChange::Describe {
+                        index: space.index,
+                        label,
+                    }),
+                None => Some(SpaceChange::Create {
+                    label,
+                    display_uuid: config.assigned_display(stable_index),
+                }),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yabai::transport::fixtures;
+
+    #[test]
+    fn plans_missing_and_undescribed_spaces() {
+        let config: Config = toml::from_str(
+            r#"
+            displays.external = "display-2"
+
+            [spaces.2]
+            description = "Mail"
+
+            [spaces.3]
+            description = "Music"
+            display = "external"
+
+            [spaces.4]
+            description = "Notes"
+            "#,
+        )
+        .unwrap();
+        let spaces = [
+            fixtures::space(1, Some("1:")),
+            fixtures::space(2, Some("2:")),
+            fixtures::space(3, Some("4: Journal")),
+            fixtures::space(4, None),
+        ];
+        let stable_indexes: Vec<_> = (1..=4)
+            .map(|index| StableSpaceIndex::try_from(index).unwrap())
+            .collect();
+
+        assert_eq!(
+            vec![
+                SpaceChange::Describe {
+                    index: SpaceIndex(2),
+                    label: "2: Mail".to_owned(),
+                },
+                SpaceChange::Create {
+                    label: "3: Music".to_owned(),
+                    display_uuid: Some("display-2"),
+                },
+            ],
+            plan_changes(&stable_indexes, &spaces, &config)
+        );
+    }
+}
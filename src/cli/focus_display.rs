@@ -0,0 +1,71 @@
+use anyhow::Context;
+
+use crate::{
+    config::FocusConfig,
+    display_target::DisplayTarget,
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::FocusSpaceByIndex,
+        transport::{Display, Space},
+    },
+};
+
+use super::rebalance::query_spaces_and_displays;
+
+/// Focuses the visible space on the target display.
+pub fn focus_display(target: &DisplayTarget, focus_config: &FocusConfig) -> anyhow::Result<()> {
+    let (spaces, displays) = query_spaces_and_displays()?;
+    let active_display = find_active_display(&spaces, &displays)?;
+    let target_display = target.resolve(&displays, active_display, focus_config)?;
+
+    if target_display.index == active_display.index {
+        log::info!("The display {target} is already focused");
+        return Ok(());
+    }
+
+    let space = find_visible_space(&spaces, target_display)?;
+    log::info!(
+        "Focusing space {:?} visible on display {:?}",
+        space.index,
+        target_display.index
+    );
+
+    execute_yabai_cmd(&FocusSpaceByIndex::new(space.index))
+        .with_context(|| format!("Cannot focus space {:?}", space.index))
+}
+
+/// The display with the focused space.
+pub(crate) fn find_active_display<'d>(
+    spaces: &[Space],
+    displays: &'d [Display],
+) -> anyhow::Result<&'d Display> {
+    let active_space = spaces
+        .iter()
+        .find(|space| space.has_focus)
+        .context("No space has focus")?;
+
+    displays
+        .iter()
+        .find(|display| display.index == active_space.display_index)
+        .with_context(|| {
+            format!(
+                "Cannot find the display of the active space {:?}",
+                active_space.index
+            )
+        })
+}
+
+pub(crate) fn find_visible_space<'s>(
+    spaces: &'s [Space],
+    display: &Display,
+) -> anyhow::Result<&'s Space> {
+    spaces
+        .iter()
+        .find(|space| space.display_index == display.index && space.is_visible)
+        .with_context(|| {
+            format!(
+                "Cannot find the visible space on display {:?}",
+                display.index
+            )
+        })
+}
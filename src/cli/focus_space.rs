@@ -9,21 +9,30 @@ use crate::{
         self,
         cli::execute_yabai_cmd,
         command::{FocusSpaceByIndex, QuerySpaces},
+        transport::Space,
     },
 };
 
+use super::rebalance::query_spaces_and_displays;
+
 #[derive(ValueEnum, Clone, Copy, Debug)]
 pub enum NextOrPrevious {
     Next,
     Previous,
 }
 
-pub fn focus_next_or_previous_space(next_or_previous: NextOrPrevious) -> anyhow::Result<()> {
-    let spaces_in_display = execute_yabai_cmd(&QuerySpaces {
+pub fn focus_next_or_previous_space(
+    next_or_previous: NextOrPrevious,
+    skip_native_fullscreen: bool,
+) -> anyhow::Result<()> {
+    let spaces_in_display: Vec<_> = execute_yabai_cmd(&QuerySpaces {
         only_current_display: true,
     })
     .context("Could not get spaes in the current display")?
-    .context("Could not parse spaces")?;
+    .context("Could not parse spaces")?
+    .into_iter()
+    .filter(|space| !skip_native_fullscreen || !space.is_native_fullscreen || space.has_focus)
+    .collect();
 
     let active_space_index = spaces_in_display
         .iter()
@@ -73,7 +82,7 @@ pub fn focus_space_by_label(
         {
             log::debug!("Space with label prefix {label_prefix} not found. Creating a new one");
 
-            let created_space = create_space_with_label(label_prefix.to_owned())
+            let created_space = create_space_with_label(label_prefix.to_owned(), None)
                 .with_context(|| format!("Could not create space with label {label_prefix}"))?;
 
             Ok((Cow::Owned(created_space), label_prefix))
@@ -135,3 +144,22 @@ fn find_space_with_prefix<'s>(
         ),
     }
 }
+
+pub(crate) fn find_space_with_uuid<'s>(
+    spaces: &'s [Space],
+    uuid: &str,
+) -> anyhow::Result<&'s Space> {
+    spaces
+        .iter()
+        .find(|space| space.uuid == uuid)
+        .with_context(|| format!("Cannot find the space with UUID {uuid:?}"))
+}
+
+pub(crate) fn focus_space_with_uuid(uuid: &str) -> anyhow::Result<()> {
+    let (spaces, _) = query_spaces_and_displays()?;
+    let space = find_space_with_uuid(&spaces, uuid)?;
+
+    log::info!("Focusing space {:?}", space.index);
+    execute_yabai_cmd(&FocusSpaceByIndex::new(space.index))
+        .with_context(|| format!("Cannot focus space {:?}", space.index))
+}
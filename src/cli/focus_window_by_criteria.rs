@@ -0,0 +1,143 @@
+use anyhow::Context;
+
+use crate::{
+    window_criteria::WindowCriteria,
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::{FocusSpaceByIndex, FocusWindowById, QuerySpaces, QueryWindows},
+        transport::{Space, Window},
+    },
+};
+
+/// Focuses a window that matches the criteria, switching to its space when needed.
+///
+/// A matching window on a visible space is preferred. When the active window already matches,
+/// the next matching window is focused, so repeated invocations cycle through all matches.
+pub fn focus_window_by_criteria(criteria: &WindowCriteria) -> anyhow::Result<()> {
+    let windows = execute_yabai_cmd(&QueryWindows)
+        .context("Could not query windows")?
+        .context("Could not parse windows")?;
+
+    let window_to_focus = select_window_to_focus(&windows, criteria)
+        .with_context(|| format!("No window matches {criteria}"))?;
+
+    let spaces = execute_yabai_cmd(&QuerySpaces {
+        only_current_display: false,
+    })
+    .context("Could not query spaces")?
+    .context("Could not parse spaces")?;
+
+    focus_window_and_its_space(window_to_focus, &spaces)
+}
+
+/// Focuses the space of the window first when it is not visible.
+pub(crate) fn focus_window_and_its_space(window: &Window, spaces: &[Space]) -> anyhow::Result<()> {
+    let space_is_visible = spaces
+        .iter()
+        .find(|space| space.index == window.space_index)
+        .is_some_and(|space| space.is_visible);
+
+    if !space_is_visible {
+        log::info!(
+            "Focusing space {:?} that contains window {:?}",
+            window.space_index,
+            window.id
+        );
+        execute_yabai_cmd(&FocusSpaceByIndex::new(window.space_index))
+            .with_context(|| format!("Cannot focus space {:?}", window.space_index))?;
+    }
+
+    log::info!(
+        "Focusing window {:?} ({}: {})",
+        window.id,
+        window.app,
+        window.title
+    );
+    execute_yabai_cmd(&FocusWindowById::new(window.id))
+        .with_context(|| format!("Cannot focus window {:?}", window.id))
+}
+
+fn select_window_to_focus<'w>(
+    windows: &'w [Window],
+    criteria: &WindowCriteria,
+) -> Option<&'w Window> {
+    let mut matching_windows: Vec<_> = windows
+        .iter()
+        .filter(|window| criteria.matches(window))
+        .collect();
+    matching_windows.sort_by_key(|window| (window.space_index, window.id.0));
+
+    if let Some(active_position) = matching_windows.iter().position(|window| window.has_focus) {
+        let next_position = (active_position + 1) % matching_windows.len();
+        return Some(matching_windows[next_position]);
+    }
+
+    matching_windows
+        .iter()
+        .find(|window| window.is_visible)
+        .or(matching_windows.first())
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yabai::transport::{fixtures, Frame, SpaceIndex};
+
+    fn window(id: u32, app: &str, space_index: u32, is_visible: bool, has_focus: bool) -> Window {
+        Window {
+            app: app.to_owned(),
+            space_index: SpaceIndex(space_index),
+            is_visible,
+            has_focus,
+            ..fixtures::window(id, Frame::default())
+        }
+    }
+
+    fn criteria(app: &str) -> WindowCriteria {
+        WindowCriteria {
+            app: Some(app.to_owned()),
+            title_regex: None,
+        }
+    }
+
+    #[test]
+    fn prefers_visible_window() {
+        let windows = [
+            window(1, "Slack", 1, false, false),
+            window(2, "Slack", 2, true, false),
+            window(3, "Firefox", 2, true, true),
+        ];
+
+        let selected = select_window_to_focus(&windows, &criteria("slack")).unwrap();
+        assert_eq!(2, selected.id.0);
+    }
+
+    #[test]
+    fn cycles_through_matching_windows() {
+        let windows = [
+            window(3, "Slack", 1, true, false),
+            window(1, "Slack", 2, true, true),
+            window(2, "Slack", 3, false, false),
+        ];
+
+        let selected = select_window_to_focus(&windows, &criteria("Slack")).unwrap();
+        assert_eq!(2, selected.id.0);
+
+        let windows = [
+            window(3, "Slack", 1, true, false),
+            window(1, "Slack", 2, true, false),
+            window(2, "Slack", 3, true, true),
+        ];
+
+        let selected = select_window_to_focus(&windows, &criteria("Slack")).unwrap();
+        assert_eq!(3, selected.id.0);
+    }
+
+    #[test]
+    fn finds_nothing_without_matches() {
+        let windows = [window(1, "Firefox", 1, true, true)];
+
+        assert!(select_window_to_focus(&windows, &criteria("Slack")).is_none());
+    }
+}
@@ -2,15 +2,23 @@ use anyhow::{anyhow, Context};
 use log::{info, warn};
 
 use crate::{
-    position::{get_element_to_focus, Direction},
+    config::{FocusConfig, StackConfig},
+    position::{get_element_to_focus, get_element_to_wrap_to, Direction},
     yabai::{
         cli::execute_yabai_cmd,
-        command::{FocusSpaceByIndex, FocusWindowById, QueryDisplays, QuerySpaces, QueryWindows},
+        command::{
+            FocusSpaceByIndex, FocusStackWindow, FocusWindowById, QueryDisplays, QuerySpaces,
+            QueryWindows, StackWindowSelector,
+        },
         transport::{Display, Frame, Space, Window},
     },
 };
 
-pub fn focus_window_in_direction(direction: Direction) -> anyhow::Result<()> {
+pub fn focus_window_in_direction(
+    direction: Direction,
+    stack_config: &StackConfig,
+    focus_config: &FocusConfig,
+) -> anyhow::Result<()> {
     let windows = execute_yabai_cmd(&QueryWindows)
         .context("Could not query windows")?
         .context("Could not parse windows")?;
@@ -18,22 +26,37 @@ pub fn focus_window_in_direction(direction: Direction) -> anyhow::Result<()> {
     let active_ui_element =
         find_active_ui_element(&windows).context("Could not find the active UI element")?;
 
+    if let ActiveUIElement::Window(active_window) = active_ui_element {
+        if let Some(selector) =
+            get_stack_window_to_focus(active_window, &windows, direction, stack_config)
+        {
+            info!("Focusing window {selector} in the stack of the active window");
+
+            return execute_yabai_cmd(&FocusStackWindow { selector })
+                .with_context(|| format!("Could not focus window {selector}"));
+        }
+    }
+
     let other_windows: Vec<_> = windows
         .iter()
         .filter(|window| window.is_visible)
         .filter(|window| match active_ui_element {
-            ActiveUIElement::Window(active_window) => !std::ptr::eq(*window, active_window),
+            ActiveUIElement::Window(active_window) => {
+                !std::ptr::eq(*window, active_window) && !window.is_stacked_with(active_window)
+            }
             ActiveUIElement::Space(..) => true,
         })
         .collect();
 
     let focused_frame: &Frame = active_ui_element.as_ref();
 
-    if let Some(window_to_focus) = get_element_to_focus(focused_frame, &other_windows, direction) {
-        info!("Focusing window with ID {}", window_to_focus.id.0);
-
-        let _ = execute_yabai_cmd(&FocusWindowById::new(window_to_focus.id))
-            .with_context(|| format!("Could not focus window with ID {}", window_to_focus.id.0));
+    if let Some(window_to_focus) = get_element_to_focus(
+        focused_frame,
+        &other_windows,
+        direction,
+        focus_config.scoring,
+    ) {
+        focus_window(window_to_focus);
     } else {
         warn!("No window in direction {:?}", direction);
 
@@ -58,17 +81,28 @@ pub fn focus_window_in_direction(direction: Direction) -> anyhow::Result<()> {
             })
             .collect();
 
-        match get_element_to_focus(focused_frame, &spaces_with_frames, direction) {
-            Some(space_to_focus) => {
-                info!("Focusing space with index {:?}", space_to_focus.space.index);
-
-                let _ = execute_yabai_cmd(&FocusSpaceByIndex::new(space_to_focus.space.index))
-                    .with_context(|| {
-                        format!(
-                            "Could not focus space with index {:?}",
-                            space_to_focus.space.index
-                        )
-                    });
+        match get_element_to_focus(
+            focused_frame,
+            &spaces_with_frames,
+            direction,
+            focus_config.scoring,
+        ) {
+            Some(space_to_focus) => focus_space(space_to_focus),
+
+            None if focus_config.wrap => {
+                info!("No space in direction {direction:?}. Wrapping around");
+
+                if let Some(window_to_focus) =
+                    get_element_to_wrap_to(focused_frame, &other_windows, direction)
+                {
+                    focus_window(window_to_focus);
+                } else if let Some(space_to_focus) =
+                    get_element_to_wrap_to(focused_frame, &spaces_with_frames, direction)
+                {
+                    focus_space(space_to_focus);
+                } else {
+                    warn!("Nothing to wrap around to in direction {direction:?}");
+                }
             }
 
             None => {
@@ -80,6 +114,61 @@ pub fn focus_window_in_direction(direction: Direction) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn focus_window(window: &Window) {
+    info!("Focusing window with ID {}", window.id.0);
+
+    let _ = execute_yabai_cmd(&FocusWindowById::new(window.id))
+        .with_context(|| format!("Could not focus window with ID {}", window.id.0));
+}
+
+fn focus_space(space: &SpaceWithFrame) {
+    info!("Focusing space with index {:?}", space.space.index);
+
+    let _ = execute_yabai_cmd(&FocusSpaceByIndex::new(space.space.index))
+        .with_context(|| format!("Could not focus space with index {:?}", space.space.index));
+}
+
+/// Stacked windows share the same frame, so focusing windows in the stack cannot be based on their
+/// frames. Instead, configured directions cycle through the stack until they reach its first or
+/// last window. Then, the focus leaves the stack.
+fn get_stack_window_to_focus(
+    active_window: &Window,
+    windows: &[Window],
+    direction: Direction,
+    stack_config: &StackConfig,
+) -> Option<StackWindowSelector> {
+    if active_window.stack_index == 0 {
+        return None;
+    }
+
+    let selector = if direction == stack_config.next_direction.into() {
+        StackWindowSelector::Next
+    } else if direction == stack_config.previous_direction.into() {
+        StackWindowSelector::Previous
+    } else {
+        return None;
+    };
+
+    let last_stack_index = windows
+        .iter()
+        .filter(|window| window.is_stacked_with(active_window))
+        .map(|window| window.stack_index)
+        .max()
+        .unwrap_or(active_window.stack_index);
+
+    let is_at_stack_edge = match selector {
+        StackWindowSelector::Next => active_window.stack_index >= last_stack_index,
+        StackWindowSelector::Previous => active_window.stack_index <= 1,
+    };
+
+    if is_at_stack_edge {
+        info!("The active window is at the edge of its stack. Leaving the stack");
+        None
+    } else {
+        Some(selector)
+    }
+}
+
 enum ActiveUIElement<'w> {
     Space(SpaceWithFrame),
     Window(&'w Window),
@@ -147,3 +236,71 @@ impl AsRef<Frame> for SpaceWithFrame {
         &self.frame
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yabai::transport::fixtures;
+
+    fn window(id: u32, x: f32, stack_index: u32) -> Window {
+        Window {
+            stack_index,
+            ..fixtures::window(
+                id,
+                Frame {
+                    x,
+                    y: 0.0,
+                    width: 100.0,
+                    height: 100.0,
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn cycles_within_stack_until_its_edge() {
+        let windows = [
+            window(1, 0.0, 1),
+            window(2, 0.0, 2),
+            window(3, 0.0, 3),
+            window(4, 100.0, 0),
+        ];
+        let config = StackConfig::default();
+
+        assert_eq!(
+            Some(StackWindowSelector::Next),
+            get_stack_window_to_focus(&windows[1], &windows, Direction::South, &config)
+        );
+        assert_eq!(
+            Some(StackWindowSelector::Previous),
+            get_stack_window_to_focus(&windows[1], &windows, Direction::North, &config)
+        );
+        assert_eq!(
+            None,
+            get_stack_window_to_focus(&windows[2], &windows, Direction::South, &config)
+        );
+        assert_eq!(
+            None,
+            get_stack_window_to_focus(&windows[0], &windows, Direction::North, &config)
+        );
+        assert_eq!(
+            None,
+            get_stack_window_to_focus(&windows[1], &windows, Direction::East, &config)
+        );
+    }
+
+    #[test]
+    fn does_not_cycle_outside_of_stack() {
+        let windows = [window(1, 0.0, 0), window(2, 100.0, 0)];
+
+        assert_eq!(
+            None,
+            get_stack_window_to_focus(
+                &windows[0],
+                &windows,
+                Direction::South,
+                &StackConfig::default()
+            )
+        );
+    }
+}
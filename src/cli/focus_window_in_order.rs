@@ -0,0 +1,220 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use anyhow::Context;
+
+use crate::{
+    config::DisplayOrder,
+    label::Labelable,
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::{QueryDisplays, QuerySpaces, QueryWindows},
+        transport::{Display, Space, Window},
+    },
+};
+
+use super::{
+    focus_space::NextOrPrevious, focus_window_by_criteria::focus_window_and_its_space,
+    reorder::sort_displays,
+};
+
+/// Focuses the next or previous managed window across all spaces, switching spaces as needed.
+///
+/// Windows are ordered by their display (left to right), then by the stable index of their space
+/// (spaces without one come last), then by their position within the space. The order wraps
+/// around. Hidden, minimized and floating windows are skipped.
+pub fn focus_next_or_previous_window(next_or_previous: NextOrPrevious) -> anyhow::Result<()> {
+    let windows = execute_yabai_cmd(&QueryWindows)
+        .context("Could not query windows")?
+        .context("Could not parse windows")?;
+
+    let spaces = execute_yabai_cmd(&QuerySpaces {
+        only_current_display: false,
+    })
+    .context("Could not query spaces")?
+    .context("Could not parse spaces")?;
+
+    let displays = execute_yabai_cmd(&QueryDisplays)
+        .context("Could not query displays")?
+        .context("Could not parse displays")?;
+
+    let ordered_windows = order_windows(&windows, &spaces, &displays);
+    let Some(window_to_focus) = select_window(&ordered_windows, next_or_previous) else {
+        log::warn!("There are no windows to focus");
+        return Ok(());
+    };
+
+    focus_window_and_its_space(window_to_focus, &spaces)
+}
+
+fn order_windows<'w>(
+    windows: &'w [Window],
+    spaces: &[Space],
+    displays: &[Display],
+) -> Vec<&'w Window> {
+    let display_positions: HashMap<_, _> = sort_displays(displays, &DisplayOrder::LeftToRight)
+        .into_iter()
+        .enumerate()
+        .map(|(position, display)| (display.index.0, position))
+        .collect();
+
+    let stable_indexes: HashMap<_, _> = spaces
+        .iter()
+        .map(|space| {
+            let stable_index = space
+                .label
+                .as_deref()
+                .and_then(|label| Space::parse_index(label).ok());
+            (space.index.0, stable_index)
+        })
+        .collect();
+
+    let space_key = |window: &Window| {
+        let stable_index = stable_indexes.get(&window.space_index.0).copied().flatten();
+        // NOTE: `None` sorts first, but spaces without a stable index should come last
+        (stable_index.is_none(), stable_index, window.space_index)
+    };
+
+    let mut ordered_windows: Vec<_> = windows
+        .iter()
+        .filter(|window| !window.is_hidden && !window.is_minimized && !window.is_floating)
+        .collect();
+
+    ordered_windows.sort_by(|window_a, window_b| {
+        display_positions
+            .get(&window_a.display_index.0)
+            .cmp(&display_positions.get(&window_b.display_index.0))
+            .then_with(|| space_key(window_a).cmp(&space_key(window_b)))
+            .then_with(|| compare_positions(window_a, window_b))
+            .then_with(|| window_a.id.0.cmp(&window_b.id.0))
+    });
+
+    ordered_windows
+}
+
+/// Left to right, then top to bottom.
+fn compare_positions(window_a: &Window, window_b: &Window) -> Ordering {
+    f32::total_cmp(&window_a.frame.x, &window_b.frame.x)
+        .then(f32::total_cmp(&window_a.frame.y, &window_b.frame.y))
+}
+
+/// Without a focused window in the order, the first (or last) window is selected.
+fn select_window<'w>(
+    ordered_windows: &[&'w Window],
+    next_or_previous: NextOrPrevious,
+) -> Option<&'w Window> {
+    if ordered_windows.is_empty() {
+        return None;
+    }
+
+    let len = ordered_windows.len();
+    let active_position = ordered_windows.iter().position(|window| window.has_focus);
+
+    let position = match (next_or_previous, active_position) {
+        (NextOrPrevious::Next, Some(position)) => (position + 1) % len,
+        (NextOrPrevious::Next, None) => 0,
+        (NextOrPrevious::Previous, Some(position)) => (position + len - 1) % len,
+        (NextOrPrevious::Previous, None) => len - 1,
+    };
+
+    Some(ordered_windows[position])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yabai::transport::{fixtures, DisplayIndex, Frame, SpaceIndex};
+
+    fn space(index: u32, label: Option<&str>, display_index: u32) -> Space {
+        Space {
+            display_index: DisplayIndex(display_index),
+            ..fixtures::space(index, label)
+        }
+    }
+
+    fn window(id: u32, space_index: u32, display_index: u32, x: f32) -> Window {
+        Window {
+            space_index: SpaceIndex(space_index),
+            display_index: DisplayIndex(display_index),
+            ..fixtures::window(
+                id,
+                Frame {
+                    x,
+                    ..Frame::default()
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn orders_windows_by_display_space_and_position() {
+        // Display 2 is left of display 1
+        let displays = [
+            fixtures::display(1, Frame::default()),
+            fixtures::display(
+                2,
+                Frame {
+                    x: -1000.0,
+                    ..Frame::default()
+                },
+            ),
+        ];
+        let spaces = [
+            space(1, Some("2:"), 1),
+            space(2, None, 1),
+            space(3, Some("1:"), 1),
+            space(4, Some("3:"), 2),
+        ];
+        let windows = [
+            window(1, 1, 1, 500.0),
+            window(2, 1, 1, 0.0),
+            window(3, 2, 1, 0.0),
+            window(4, 3, 1, 0.0),
+            window(5, 4, 2, -1000.0),
+            Window {
+                is_floating: true,
+                ..window(6, 4, 2, -1000.0)
+            },
+            Window {
+                is_minimized: true,
+                ..window(7, 1, 1, 0.0)
+            },
+        ];
+
+        let ordered_ids: Vec<_> = order_windows(&windows, &spaces, &displays)
+            .into_iter()
+            .map(|window| window.id.0)
+            .collect();
+
+        assert_eq!(vec![5, 4, 2, 1, 3], ordered_ids);
+    }
+
+    #[test]
+    fn selects_next_and_previous_window_with_wrapping() {
+        let windows = [
+            window(1, 1, 1, 0.0),
+            window(2, 1, 1, 100.0),
+            Window {
+                has_focus: true,
+                ..window(3, 1, 1, 200.0)
+            },
+        ];
+        let ordered_windows: Vec<_> = windows.iter().collect();
+
+        let select = |next_or_previous| {
+            select_window(&ordered_windows, next_or_previous).map(|window| window.id.0)
+        };
+        assert_eq!(Some(1), select(NextOrPrevious::Next));
+        assert_eq!(Some(2), select(NextOrPrevious::Previous));
+
+        assert_eq!(
+            Some(1),
+            select_window(&ordered_windows[..2], NextOrPrevious::Next).map(|window| window.id.0)
+        );
+        assert_eq!(
+            Some(2),
+            select_window(&ordered_windows[..2], NextOrPrevious::Previous)
+                .map(|window| window.id.0)
+        );
+        assert!(select_window(&[], NextOrPrevious::Next).is_none());
+    }
+}
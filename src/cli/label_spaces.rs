@@ -1,5 +1,5 @@
 use crate::{
-    label::partition::partition_labelables,
+    label::{partition::partition_labelables, space::apply_remembered_layout},
     yabai::{self, cli::execute_yabai_cmd, command::QuerySpaces, transport::Space},
 };
 use anyhow::Context;
@@ -12,7 +12,13 @@ pub fn label_spaces() -> anyhow::Result<()> {
     .context("Could not query spaces")?
     .context("Could not parse spaces")?;
 
-    let partitioned_spaces = partition_labelables(spaces.into_iter());
+    // NOTE: yabai cannot label some native fullscreen spaces and they disappear when the window
+    // leaves fullscreen anyway
+    let partitioned_spaces = partition_labelables(
+        spaces
+            .into_iter()
+            .filter(|space| !space.is_native_fullscreen),
+    );
 
     let incorrectly_labeled_spaces = partitioned_spaces.incorrectly_labeled();
     if !incorrectly_labeled_spaces.is_empty() {
@@ -60,6 +66,7 @@ pub fn label_spaces() -> anyhow::Result<()> {
                     index = space.index
                 )
             })?;
+        apply_remembered_layout(space.index, index);
     }
 
     info!("Labeled {spaces_to_label_len} spaces");
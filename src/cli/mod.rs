@@ -1,9 +1,25 @@
+pub mod apply_rules;
 pub mod destroy_spaces;
+pub mod ensure_spaces;
+pub mod focus_display;
 pub mod focus_space;
+pub mod focus_window_by_criteria;
 pub mod focus_window_in_direction;
+pub mod focus_window_in_order;
 pub mod label_spaces;
-pub mod move_space_in_direction;
+pub mod move_space;
 pub mod move_window_in_direction;
+pub mod move_window_to_display;
 pub mod move_window_to_space;
+pub mod placeholder_spaces;
+pub mod profile;
+pub mod raise_or_run;
+pub mod rebalance;
 pub mod reorder;
+pub mod resize_window;
 pub mod set_space_label;
+pub mod set_space_layout;
+pub mod stack_window;
+pub mod swap_displays;
+pub mod swap_window;
+pub mod toggle_window;
@@ -0,0 +1,221 @@
+use anyhow::Context;
+use clap::Args;
+
+use crate::{
+    config::FocusConfig,
+    display_target::DisplayTarget,
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::SendSpaceToDisplay,
+        transport::{Display, Space},
+    },
+};
+
+use super::{
+    focus_display::{find_active_display, find_visible_space},
+    focus_space::{find_space_with_uuid, focus_space_with_uuid},
+    placeholder_spaces::{create_placeholder_space, destroy_placeholder_space},
+    rebalance::query_spaces_and_displays,
+};
+
+#[derive(Args)]
+pub struct MoveSpaceOptions {
+    /// Yabai does not allow moving the last space from a display.
+    ///
+    /// This flag will make it so that in this situation, an extra space will be created for
+    /// that display in the background, so moving the active space is possible.
+    #[arg(long, default_value_t = false)]
+    pub create_extra_space_if_last_on_display: bool,
+
+    /// Keep the focus on the moved space.
+    #[arg(long, default_value_t = false)]
+    pub follow: bool,
+
+    /// Also send the visible space of the target display to the source display, exchanging the
+    /// visible spaces of both displays.
+    ///
+    /// A single space on the source display is handled without `--create-extra-space-if-last-on-display`.
+    #[arg(long, default_value_t = false)]
+    pub swap: bool,
+}
+
+/// Moves the active space to the target display.
+pub fn move_space_to_display(
+    target: &DisplayTarget,
+    options: &MoveSpaceOptions,
+    focus_config: &FocusConfig,
+) -> anyhow::Result<()> {
+    let (spaces, displays) = query_spaces_and_displays()?;
+
+    let active_space = spaces
+        .iter()
+        .find(|space| space.has_focus)
+        .context("No space has focus")?;
+
+    anyhow::ensure!(
+        !active_space.is_native_fullscreen,
+        "Cannot move the native fullscreen space {:?} to another display",
+        active_space.index
+    );
+
+    let active_display = find_active_display(&spaces, &displays)?;
+    let target_display = target.resolve(&displays, active_display, focus_config)?;
+
+    if target_display.index == active_display.index {
+        log::info!(
+            "The space {:?} is already on display {target}",
+            active_space.index
+        );
+        return Ok(());
+    }
+
+    if options.swap {
+        let swapped_spaces = swap_visible_spaces(&spaces, active_display, target_display)?;
+
+        // Focus the space that should end up with the focus last, so both displays show the
+        // exchanged spaces.
+        let (first, last) = if options.follow {
+            (
+                &swapped_spaces.target_space_uuid,
+                &swapped_spaces.source_space_uuid,
+            )
+        } else {
+            (
+                &swapped_spaces.source_space_uuid,
+                &swapped_spaces.target_space_uuid,
+            )
+        };
+        focus_space_with_uuid(first)?;
+        return focus_space_with_uuid(last);
+    }
+
+    send_space_to_display(
+        active_space,
+        active_display,
+        target_display,
+        options.create_extra_space_if_last_on_display,
+    )?;
+
+    if options.follow {
+        focus_space_with_uuid(&active_space.uuid)?;
+    }
+
+    Ok(())
+}
+
+/// UUIDs of the spaces exchanged by [`swap_visible_spaces`].
+pub(crate) struct SwappedSpaces {
+    /// The space that was visible on the source display and is now on the target display.
+    pub source_space_uuid: String,
+    /// The space that was visible on the target display and is now on the source display.
+    pub target_space_uuid: String,
+}
+
+/// Sends the visible space of `source_display` to `target_display` and the visible space of
+/// `target_display` back to `source_display`.
+///
+/// Yabai does not allow moving the last space from a display. When `source_display` has a single
+/// space, a placeholder space is created on it for the duration of the swap.
+pub(crate) fn swap_visible_spaces(
+    spaces: &[Space],
+    source_display: &Display,
+    target_display: &Display,
+) -> anyhow::Result<SwappedSpaces> {
+    let source_space = find_visible_space(spaces, source_display)?;
+    let target_space = find_visible_space(spaces, target_display)?;
+
+    for space in [source_space, target_space] {
+        anyhow::ensure!(
+            !space.is_native_fullscreen,
+            "Cannot swap the native fullscreen space {:?}",
+            space.index
+        );
+    }
+
+    let placeholder_uuid = if source_display.spaces.len() == 1 {
+        log::info!(
+            "Display {:?} has a single space. Creating a placeholder space to allow the swap",
+            source_display.index
+        );
+        Some(create_placeholder_space(source_display)?)
+    } else {
+        None
+    };
+
+    // NOTE: indexes change after every move, so spaces are looked up by their UUIDs
+    let (spaces, displays) = query_spaces_and_displays()?;
+    let source_display = find_display_with_uuid(&displays, &source_display.uuid)?;
+    let target_display = find_display_with_uuid(&displays, &target_display.uuid)?;
+    send_space_to_display(
+        find_space_with_uuid(&spaces, &source_space.uuid)?,
+        source_display,
+        target_display,
+        false,
+    )?;
+
+    let (spaces, displays) = query_spaces_and_displays()?;
+    let source_display = find_display_with_uuid(&displays, &source_display.uuid)?;
+    let target_display = find_display_with_uuid(&displays, &target_display.uuid)?;
+    send_space_to_display(
+        find_space_with_uuid(&spaces, &target_space.uuid)?,
+        target_display,
+        source_display,
+        false,
+    )?;
+
+    if let Some(placeholder_uuid) = placeholder_uuid {
+        destroy_placeholder_space(&placeholder_uuid)?;
+    }
+
+    Ok(SwappedSpaces {
+        source_space_uuid: source_space.uuid.clone(),
+        target_space_uuid: target_space.uuid.clone(),
+    })
+}
+
+/// Sends `space` from `source_display` to `target_display`.
+///
+/// Yabai does not allow moving the last space from a display. When `create_extra_space_if_last_on_display`
+/// is set, an extra space is created on the source display in that situation, so the move is possible.
+/// The extra space is remembered as a placeholder and cleaned up according to the `placeholders`
+/// config section.
+pub fn send_space_to_display(
+    space: &Space,
+    source_display: &Display,
+    target_display: &Display,
+    create_extra_space_if_last_on_display: bool,
+) -> anyhow::Result<()> {
+    if source_display.spaces.len() == 1 {
+        if create_extra_space_if_last_on_display {
+            log::info!("The space {:?} is the only one in the display {:?}. Creating a new one to allow moving the space", space.index, source_display.index);
+
+            create_placeholder_space(source_display)?;
+        } else {
+            log::warn!("The space {:?} is the only one in the display {:?}. Yabai will most likely fail to send it to another display", space.index, source_display.index);
+        }
+    }
+
+    log::info!(
+        "Sending the space {:?} to display {:?}",
+        space.index,
+        target_display.index
+    );
+
+    execute_yabai_cmd(&SendSpaceToDisplay::new(space.index, target_display.index)).with_context(
+        || {
+            format!(
+                "Could not send space {} to display {}",
+                *space.index, *target_display.index
+            )
+        },
+    )?;
+
+    Ok(())
+}
+
+fn find_display_with_uuid<'d>(displays: &'d [Display], uuid: &str) -> anyhow::Result<&'d Display> {
+    displays
+        .iter()
+        .find(|display| display.uuid == uuid)
+        .with_context(|| format!("Cannot find the display with UUID {uuid:?}"))
+}
@@ -1,7 +1,9 @@
 use anyhow::Context;
 
 use crate::{
-    position::{get_element_to_focus, Direction},
+    config::FocusConfig,
+    display_target::DisplayTarget,
+    position::{get_element_to_focus, CardinalDirection, Direction},
     yabai::{
         self,
         cli::execute_yabai_cmd,
@@ -9,13 +11,21 @@ use crate::{
     },
 };
 
-pub fn move_window_in_direction(direction: Direction) -> anyhow::Result<()> {
+pub fn move_window_in_direction(
+    direction: Direction,
+    focus_config: &FocusConfig,
+) -> anyhow::Result<()> {
     let IntrospectedWindows {
         active_window,
         other_visible_windows,
     } = introspect_windows()?;
 
-    match get_element_to_focus(&active_window.frame, &other_visible_windows, direction) {
+    match get_element_to_focus(
+        &active_window.frame,
+        &other_visible_windows,
+        direction,
+        focus_config.scoring,
+    ) {
         Some(target_window) => {
             log::trace!("The closest window in direction {direction:?} is {target_window:?}");
 
@@ -25,10 +35,15 @@ pub fn move_window_in_direction(direction: Direction) -> anyhow::Result<()> {
                     active_window.space_index
                 );
 
-                execute_yabai_cmd(&yabai::command::WarpWindow::new(
-                    yabai::command::WarpWindowArg::Direction(direction),
-                ))
-                .with_context(|| format!("Cannot warp window in direction {direction:?}"))
+                // NOTE: yabai only warps in cardinal directions
+                let warp_arg = if direction.is_cardinal() {
+                    yabai::command::WarpWindowArg::Direction(direction)
+                } else {
+                    yabai::command::WarpWindowArg::WindowId(target_window.id)
+                };
+
+                execute_yabai_cmd(&yabai::command::WarpWindow::new(warp_arg))
+                    .with_context(|| format!("Cannot warp window in direction {direction:?}"))
             } else {
                 log::info!(
                     "The closest window in direction {direction:?} is in another space ({:?})",
@@ -64,11 +79,17 @@ pub fn move_window_in_direction(direction: Direction) -> anyhow::Result<()> {
                                 format!("Cannot focus window {:?}", active_window.id)
                             })?;
 
-                        let opposite_direction = direction.into_opposite();
+                        let Ok(opposite_direction) = CardinalDirection::try_from(direction)
+                            .map(CardinalDirection::into_opposite)
+                        else {
+                            log::debug!("Yabai cannot warp in the direction opposite to {direction:?}. Leaving the window in place");
+                            return Ok(());
+                        };
+
                         log::info!("Now warping that window in the opposite direction ({opposite_direction:?}), so it is closer to the original space.");
 
                         execute_yabai_cmd(&yabai::command::WarpWindow::new(
-                            yabai::command::WarpWindowArg::Direction(opposite_direction),
+                            yabai::command::WarpWindowArg::Direction(opposite_direction.into()),
                         ))
                         .with_context(|| {
                             format!("Cannot warp window to in opposite direction {opposite_direction:?}",)
@@ -83,6 +104,7 @@ pub fn move_window_in_direction(direction: Direction) -> anyhow::Result<()> {
 
                         let target_space = target_window.space_index.to_string();
                         execute_yabai_cmd(&yabai::command::MoveWindowToSpace {
+                            window_id: None,
                             // TODO: convert to an enum for handling SpaceIndex and String
                             target_space_label: target_space.clone(),
                         })
@@ -99,7 +121,7 @@ pub fn move_window_in_direction(direction: Direction) -> anyhow::Result<()> {
         }
         None => {
             log::debug!("There are no windows in direction {direction:?}. Looking for spaces in that direction to move the window there");
-            let target_space = find_space_in_direction(direction)
+            let target_space = find_space_in_direction(direction, focus_config)
                 .with_context(|| format!("Cannot find a space in direction {direction:?}"))?;
             log::debug!("Found target space {:?}", target_space.index);
 
@@ -107,6 +129,7 @@ pub fn move_window_in_direction(direction: Direction) -> anyhow::Result<()> {
             log::info!("Moving the window to space {target_space_specifier}");
 
             execute_yabai_cmd(&yabai::command::MoveWindowToSpace {
+                window_id: None,
                 // TODO: convert to an enum for handling SpaceIndex and String
                 target_space_label: target_space_specifier.clone(),
             })
@@ -120,13 +143,13 @@ pub fn move_window_in_direction(direction: Direction) -> anyhow::Result<()> {
 }
 
 #[derive(Debug)]
-struct IntrospectedWindows {
-    active_window: Window,
+pub(crate) struct IntrospectedWindows {
+    pub(crate) active_window: Window,
 
-    other_visible_windows: Vec<Window>,
+    pub(crate) other_visible_windows: Vec<Window>,
 }
 
-fn introspect_windows() -> anyhow::Result<IntrospectedWindows> {
+pub(crate) fn introspect_windows() -> anyhow::Result<IntrospectedWindows> {
     let all_windows = execute_yabai_cmd(&yabai::command::QueryWindows)
         .context("Cannot query windows")?
         .context("Cannot parse windows")?;
@@ -158,7 +181,12 @@ fn introspect_windows() -> anyhow::Result<IntrospectedWindows> {
     })
 }
 
-fn find_space_in_direction(direction: Direction) -> anyhow::Result<Space> {
+/// Finds the visible space on the display in a given direction, wrapping around to the farthest
+/// display in the opposite direction when enabled.
+fn find_space_in_direction(
+    direction: Direction,
+    focus_config: &FocusConfig,
+) -> anyhow::Result<Space> {
     let spaces = execute_yabai_cmd(&yabai::command::QuerySpaces {
         only_current_display: false,
     })
@@ -183,8 +211,8 @@ fn find_space_in_direction(direction: Direction) -> anyhow::Result<Space> {
 
     log::trace!("Determined active display to be {active_space:?}");
 
-    let target_display = get_element_to_focus(&active_display.frame, &displays, direction)
-        .with_context(|| format!("Could not find a display in direction {direction:?}"))?;
+    let target_display =
+        DisplayTarget::Direction(direction).resolve(&displays, active_display, focus_config)?;
 
     log::trace!("Determined target display in direction {direction:?} to be {target_display:?}");
 
@@ -0,0 +1,65 @@
+use anyhow::Context;
+
+use crate::{
+    config::FocusConfig,
+    display_target::DisplayTarget,
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::{FocusWindowById, MoveWindowToSpace, QueryWindows},
+    },
+};
+
+use super::{
+    focus_display::{find_active_display, find_visible_space},
+    rebalance::query_spaces_and_displays,
+};
+
+/// Sends the active window to the visible space on the target display and keeps it focused.
+pub fn move_window_to_display(
+    target: &DisplayTarget,
+    focus_config: &FocusConfig,
+) -> anyhow::Result<()> {
+    let windows = execute_yabai_cmd(&QueryWindows)
+        .context("Could not query windows")?
+        .context("Could not parse windows")?;
+
+    let active_window = windows
+        .iter()
+        .find(|window| window.has_focus)
+        .context("No window has focus")?;
+
+    let (spaces, displays) = query_spaces_and_displays()?;
+    let active_display = find_active_display(&spaces, &displays)?;
+    let target_display = target.resolve(&displays, active_display, focus_config)?;
+
+    if target_display.index == active_window.display_index {
+        log::info!(
+            "The window {:?} is already on the display {target}",
+            active_window.id
+        );
+        return Ok(());
+    }
+
+    let target_space = find_visible_space(&spaces, target_display)?;
+    log::info!(
+        "Moving window {:?} to space {:?} visible on display {:?}",
+        active_window.id,
+        target_space.index,
+        target_display.index
+    );
+
+    execute_yabai_cmd(&MoveWindowToSpace {
+        window_id: Some(active_window.id),
+        target_space_label: target_space.index.to_string(),
+    })
+    .with_context(|| {
+        format!(
+            "Cannot move window {:?} to space {:?}",
+            active_window.id, target_space.index
+        )
+    })?;
+
+    log::info!("Focusing the moved window {:?}", active_window.id);
+    execute_yabai_cmd(&FocusWindowById::new(active_window.id))
+        .with_context(|| format!("Cannot focus window {:?}", active_window.id))
+}
@@ -2,10 +2,15 @@ use anyhow::Context;
 
 use crate::{
     label::{
-        space::{create_space_with_label, StableSpaceIndex},
+        space::{apply_remembered_layout, create_space_with_label, StableSpaceIndex},
         Labelable,
     },
-    yabai::{self, cli::execute_yabai_cmd, command::QuerySpaces, transport::Space},
+    yabai::{
+        self,
+        cli::execute_yabai_cmd,
+        command::QuerySpaces,
+        transport::{Space, WindowId},
+    },
 };
 
 pub fn move_window_to_space(
@@ -22,7 +27,10 @@ pub fn move_window_to_space(
         .iter()
         .find(|space| space.has_focus)
         .context("No space has focus")?;
-    let focused_space_has_only_one_window = focused_space.windows.len() == 1;
+    // NOTE: native fullscreen spaces cannot be relabeled and disappear when the window leaves
+    // fullscreen
+    let focused_space_can_be_relabeled =
+        focused_space.windows.len() == 1 && !focused_space.is_native_fullscreen;
     let focused_space_index = focused_space.index;
 
     let existing_target_space_label = spaces.into_iter().find_map(|other_space| {
@@ -34,6 +42,7 @@ pub fn move_window_to_space(
 
     let move_window_to_space = |target_space_label: &str| {
         execute_yabai_cmd(&yabai::command::MoveWindowToSpace {
+            window_id: None,
             target_space_label: target_space_label.to_owned(),
         })
         .with_context(|| format!("Cannot move window to space {}", target_space_label))
@@ -47,7 +56,7 @@ pub fn move_window_to_space(
             move_window_to_space(&target_space_label)
         }
         None if create_space_if_not_found => {
-            if focused_space_has_only_one_window {
+            if focused_space_can_be_relabeled {
                 let label = Space::label(stable_space_index, None);
 
                 log::info!("No target space with index {stable_space_index:?} found, but the current space only has one window. Relabeling the focused space to become the target space");
@@ -58,11 +67,14 @@ pub fn move_window_to_space(
                 ))
                 .with_context(|| {
                     format!("Cannot set label \"{label:?}\" for space with index {focused_space_index:?}")
-                })
+                })?;
+                apply_remembered_layout(focused_space_index, stable_space_index);
+
+                Ok(())
             } else {
                 log::info!("No target space with index {stable_space_index:?} found, creating a new space with the target index and moving the window there");
                 let label = Space::label(stable_space_index, None);
-                create_space_with_label(label.to_owned())
+                create_space_with_label(label.to_owned(), None)
                     .with_context(|| format!("Cannot create new space with label {label}"))?;
                 move_window_to_space(&label)
             }
@@ -73,3 +85,38 @@ pub fn move_window_to_space(
         ),
     }
 }
+
+/// Moves a window to the space with a stable index, creating the space when it does not exist.
+pub(crate) fn send_window_to_stable_index(
+    window_id: WindowId,
+    stable_index: StableSpaceIndex,
+) -> anyhow::Result<()> {
+    let spaces = execute_yabai_cmd(&QuerySpaces {
+        only_current_display: false,
+    })
+    .context("Cannot query yabai spaces")?
+    .context("Cannot parse spaces")?;
+
+    let existing_target_space_label = spaces.into_iter().find_map(|space| {
+        let label = space.label?;
+        (Space::parse_index(&label).ok()? == stable_index).then_some(label)
+    });
+
+    let target_space_label = match existing_target_space_label {
+        Some(label) => label,
+        None => {
+            log::info!("No space with index {stable_index:?} found, creating it");
+            let label = Space::label(stable_index, None);
+            create_space_with_label(label.clone(), None)
+                .with_context(|| format!("Cannot create new space with label {label}"))?;
+            label
+        }
+    };
+
+    log::info!("Moving window {window_id:?} to space {target_space_label}");
+    execute_yabai_cmd(&yabai::command::MoveWindowToSpace {
+        window_id: Some(window_id),
+        target_space_label: target_space_label.clone(),
+    })
+    .with_context(|| format!("Cannot move window {window_id:?} to space {target_space_label}"))
+}
@@ -0,0 +1,306 @@
+use std::collections::BTreeSet;
+
+use anyhow::Context;
+use itertools::Itertools;
+
+use crate::{
+    config::PlaceholderCleanup,
+    label::{
+        space::{apply_remembered_layout, StableSpaceIndex},
+        Labelable,
+    },
+    state::State,
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::{CreateSpace, DestoySpace, LabelSpace},
+        transport::{Display, Space},
+    },
+};
+
+use super::{focus_space::find_space_with_uuid, rebalance::query_spaces_and_displays};
+
+#[derive(Debug, PartialEq, Eq)]
+enum PlaceholderAction {
+    /// The space no longer exists or holds windows, so it is not a placeholder anymore.
+    Forget,
+    Label(StableSpaceIndex),
+    Destroy,
+}
+
+/// Creates an empty space on `display`, so that its last space can be sent to another display.
+///
+/// The space is remembered in the state and cleaned up by [`clean_up_placeholder_spaces`].
+/// Returns the UUID of the created space.
+pub(crate) fn create_placeholder_space(display: &Display) -> anyhow::Result<String> {
+    let (spaces, _) = query_spaces_and_displays()?;
+
+    execute_yabai_cmd(&CreateSpace {
+        display_index: Some(display.index),
+    })
+    .context("Cannot create a placeholder space")?;
+
+    let (new_spaces, _) = query_spaces_and_displays()?;
+    let placeholder = find_new_space(&spaces, &new_spaces)
+        .context("Cannot find the created placeholder space")?;
+    log::info!(
+        "Created placeholder space {:?} on display {:?}",
+        placeholder.index,
+        display.index
+    );
+
+    State::update(|state| {
+        state.placeholder_spaces.insert(placeholder.uuid.clone());
+    })
+    .context("Cannot remember the placeholder space")?;
+
+    Ok(placeholder.uuid.clone())
+}
+
+/// Destroys a placeholder space right away and forgets it.
+pub(crate) fn destroy_placeholder_space(uuid: &str) -> anyhow::Result<()> {
+    let (spaces, _) = query_spaces_and_displays()?;
+    let placeholder = find_space_with_uuid(&spaces, uuid)?;
+
+    log::info!("Destroying the placeholder space {:?}", placeholder.index);
+    execute_yabai_cmd(&DestoySpace {
+        index: placeholder.index,
+    })
+    .with_context(|| {
+        format!(
+            "Cannot destroy the placeholder space {:?}",
+            placeholder.index
+        )
+    })?;
+
+    State::update(|state| state.placeholder_spaces.remove(uuid))
+        .context("Cannot forget the placeholder space")?;
+
+    Ok(())
+}
+
+/// Labels or destroys the placeholder spaces created while sending spaces between displays,
+/// depending on `cleanup`.
+///
+/// Placeholders are only destroyed once another space occupies their display. Until then, they
+/// are kept and cleaned up on a later run. Placeholders that no longer exist or hold windows are
+/// forgotten with every cleanup policy.
+pub fn clean_up_placeholder_spaces(cleanup: PlaceholderCleanup) -> anyhow::Result<()> {
+    let mut placeholders = State::load()
+        .context("Cannot load the placeholder spaces")?
+        .placeholder_spaces;
+    if placeholders.is_empty() {
+        return Ok(());
+    }
+
+    let (spaces, _) = query_spaces_and_displays()?;
+    let actions = plan_cleanup(&placeholders, &spaces, cleanup);
+
+    // NOTE: destroy from the greatest index to the smallest, so indexes do not change in between
+    for (space, action) in actions
+        .into_iter()
+        .sorted_by_key(|(space, _)| std::cmp::Reverse(space.index))
+    {
+        match action {
+            PlaceholderAction::Forget => {
+                log::debug!("Forgetting the placeholder space {:?}", space.uuid);
+            }
+            PlaceholderAction::Label(stable_index) => {
+                let label = Space::label(stable_index, None);
+                log::info!("Labeling the placeholder space {:?} {label}", space.index);
+
+                execute_yabai_cmd(&LabelSpace::new(space.index, label.clone())).with_context(
+                    || format!("Cannot set label {label} to space {:?}", space.index),
+                )?;
+                apply_remembered_layout(space.index, stable_index);
+            }
+            PlaceholderAction::Destroy => {
+                log::info!("Destroying the placeholder space {:?}", space.index);
+
+                execute_yabai_cmd(&DestoySpace { index: space.index }).with_context(|| {
+                    format!("Cannot destroy the placeholder space {:?}", space.index)
+                })?;
+            }
+        }
+
+        placeholders.remove(&space.uuid);
+    }
+
+    // NOTE: placeholders that no longer exist have no space to act on
+    placeholders.retain(|uuid| spaces.iter().any(|space| &space.uuid == uuid));
+
+    State::update(|state| state.placeholder_spaces = placeholders)
+        .context("Cannot save the placeholder spaces")
+}
+
+fn plan_cleanup<'s>(
+    placeholders: &BTreeSet<String>,
+    spaces: &'s [Space],
+    cleanup: PlaceholderCleanup,
+) -> Vec<(&'s Space, PlaceholderAction)> {
+    let mut used_indexes: BTreeSet<_> = spaces
+        .iter()
+        .filter_map(|space| Space::parse_index(space.label.as_deref()?).ok())
+        .collect();
+    let mut spaces_per_display = spaces.iter().counts_by(|space| *space.display_index);
+
+    spaces
+        .iter()
+        .filter(|space| placeholders.contains(&space.uuid))
+        .filter_map(|space| {
+            if !space.windows.is_empty() {
+                return Some((space, PlaceholderAction::Forget));
+            }
+
+            match cleanup {
+                PlaceholderCleanup::Keep => None,
+                PlaceholderCleanup::Label => {
+                    let stable_index = Space::INDEX_RANGE
+                        .filter_map(|index| StableSpaceIndex::try_from(index).ok())
+                        .find(|index| !used_indexes.contains(index));
+
+                    match stable_index {
+                        Some(stable_index) => {
+                            used_indexes.insert(stable_index);
+                            Some((space, PlaceholderAction::Label(stable_index)))
+                        }
+                        None => {
+                            log::warn!(
+                                "No free stable index left for the placeholder space {:?}",
+                                space.index
+                            );
+                            None
+                        }
+                    }
+                }
+                PlaceholderCleanup::Destroy => {
+                    let display_spaces = spaces_per_display
+                        .get_mut(&*space.display_index)
+                        .expect("Every space is counted");
+
+                    (*display_spaces > 1).then(|| {
+                        *display_spaces -= 1;
+                        (space, PlaceholderAction::Destroy)
+                    })
+                }
+            }
+        })
+        .collect()
+}
+
+fn find_new_space<'s>(old_spaces: &[Space], new_spaces: &'s [Space]) -> Option<&'s Space> {
+    new_spaces
+        .iter()
+        .find(|space| !old_spaces.iter().any(|old| old.uuid == space.uuid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yabai::transport::{fixtures, DisplayIndex, WindowId};
+
+    fn space_on_display(index: u32, label: Option<&str>, display_index: u32) -> Space {
+        Space {
+            display_index: DisplayIndex(display_index),
+            ..fixtures::space(index, label)
+        }
+    }
+
+    fn plan(
+        placeholders: &[&str],
+        spaces: &[Space],
+        cleanup: PlaceholderCleanup,
+    ) -> Vec<(u32, PlaceholderAction)> {
+        let placeholders = placeholders.iter().map(|uuid| uuid.to_string()).collect();
+
+        plan_cleanup(&placeholders, spaces, cleanup)
+            .into_iter()
+            .map(|(space, action)| (space.index.0, action))
+            .collect()
+    }
+
+    #[test]
+    fn finds_new_spaces_by_uuid() {
+        let old_spaces = [fixtures::space(1, None)];
+        let new_spaces = [fixtures::space(1, None), fixtures::space(2, None)];
+
+        assert_eq!(
+            Some("space-2"),
+            find_new_space(&old_spaces, &new_spaces).map(|space| space.uuid.as_str())
+        );
+        assert!(find_new_space(&new_spaces, &new_spaces).is_none());
+    }
+
+    #[test]
+    fn labels_placeholders_with_free_stable_indexes() {
+        let spaces = [
+            space_on_display(1, Some("1:"), 1),
+            space_on_display(2, None, 1),
+            space_on_display(3, Some("2: Mail"), 2),
+            space_on_display(4, None, 2),
+        ];
+
+        assert_eq!(
+            vec![
+                (2, PlaceholderAction::Label("3".parse().unwrap())),
+                (4, PlaceholderAction::Label("4".parse().unwrap())),
+            ],
+            plan(&["space-2", "space-4"], &spaces, PlaceholderCleanup::Label)
+        );
+    }
+
+    #[test]
+    fn destroys_placeholders_only_when_the_display_has_other_spaces() {
+        let spaces = [
+            space_on_display(1, Some("1:"), 1),
+            space_on_display(2, None, 1),
+            space_on_display(3, None, 2),
+            space_on_display(4, None, 3),
+            space_on_display(5, None, 3),
+        ];
+
+        assert_eq!(
+            vec![
+                (2, PlaceholderAction::Destroy),
+                (4, PlaceholderAction::Destroy)
+            ],
+            plan(
+                &["space-2", "space-3", "space-4", "space-5"],
+                &spaces,
+                PlaceholderCleanup::Destroy
+            )
+        );
+    }
+
+    #[test]
+    fn keeps_empty_placeholders_with_the_keep_policy() {
+        let spaces = [
+            space_on_display(1, Some("1:"), 1),
+            space_on_display(2, None, 1),
+            Space {
+                windows: vec![WindowId(1)],
+                ..space_on_display(3, None, 1)
+            },
+        ];
+
+        assert_eq!(
+            vec![(3, PlaceholderAction::Forget)],
+            plan(&["space-2", "space-3"], &spaces, PlaceholderCleanup::Keep)
+        );
+    }
+
+    #[test]
+    fn forgets_placeholders_with_windows() {
+        let spaces = [
+            space_on_display(1, Some("1:"), 1),
+            Space {
+                windows: vec![WindowId(1)],
+                ..space_on_display(2, None, 1)
+            },
+        ];
+
+        assert_eq!(
+            vec![(2, PlaceholderAction::Forget)],
+            plan(&["space-2"], &spaces, PlaceholderCleanup::Destroy)
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use anyhow::Context;
+use clap::Subcommand;
+
+use crate::{
+    config::Config,
+    label::Labelable,
+    state::State,
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::{QueryDisplays, QuerySpaces, SetConfigValue, SetSpaceLayout},
+        transport::Space,
+    },
+};
+
+use super::rebalance::rebalance_spaces;
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum ProfileCommand {
+    /// Applies the profile that matches the connected displays: sends spaces to their displays,
+    /// sets space layouts and padding.
+    ///
+    /// Meant to be run from a yabai `display_added` and `display_removed` signal.
+    Apply {
+        /// Apply this profile, regardless of the connected displays.
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Prints the name of the profile that matches the connected displays.
+    Detect,
+}
+
+/// Applies the profile with a given name, or the one that matches the connected displays.
+pub fn apply_profile(name: Option<&str>, config: &Config) -> anyhow::Result<()> {
+    match name {
+        Some(name) => apply_named_profile(name, config),
+        None => match detect_profile(config)? {
+            Some(name) => apply_named_profile(&name, config),
+            None => {
+                log::info!("No profile matches the connected displays");
+                Ok(())
+            }
+        },
+    }
+}
+
+pub fn print_detected_profile(config: &Config) -> anyhow::Result<()> {
+    if let Some(name) = detect_profile(config)? {
+        println!("{name}");
+    }
+
+    Ok(())
+}
+
+fn detect_profile(config: &Config) -> anyhow::Result<Option<String>> {
+    let displays = execute_yabai_cmd(&QueryDisplays)
+        .context("Could not query displays")?
+        .context("Could not parse displays")?;
+
+    Ok(config
+        .detect_profile(displays.iter().map(|display| display.uuid.as_str()))
+        .map(ToOwned::to_owned))
+}
+
+fn apply_named_profile(name: &str, config: &Config) -> anyhow::Result<()> {
+    let profile = config
+        .profiles
+        .get(name)
+        .with_context(|| format!("There is no profile named {name:?} in the config"))?;
+    let profile_config = config
+        .with_profile(name)
+        .expect("The profile exists in the config");
+
+    log::info!("Applying profile {name:?}");
+    rebalance_spaces(&profile_config)?;
+    apply_space_layouts(&profile_config)?;
+
+    if let Some(padding) = profile.padding {
+        log::info!("Setting the padding to {padding}");
+
+        for key in [
+            "top_padding",
+            "bottom_padding",
+            "left_padding",
+            "right_padding",
+        ] {
+            execute_yabai_cmd(&SetConfigValue {
+                key,
+                value: padding.to_string(),
+            })
+            .with_context(|| format!("Cannot set {key} to {padding}"))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Sets the layouts of existing spaces and remembers them for spaces created later.
+fn apply_space_layouts(config: &Config) -> anyhow::Result<()> {
+    let spaces = execute_yabai_cmd(&QuerySpaces {
+        only_current_display: false,
+    })
+    .context("Could not query spaces")?
+    .context("Could not parse spaces")?;
+
+    let layouts: Vec<_> = config
+        .spaces
+        .iter()
+        .filter_map(|(stable_index, space_config)| Some((*stable_index, space_config.layout?)))
+        .collect();
+
+    for (stable_index, layout) in &layouts {
+        let space = spaces.iter().find(|space| {
+            space
+                .label
+                .as_deref()
+                .and_then(|label| Space::parse_index(label).ok())
+                == Some(*stable_index)
+        });
+
+        if let Some(space) = space.filter(|space| space.r#type != *layout) {
+            log::info!("Setting the layout of space {:?} to {layout}", space.index);
+            execute_yabai_cmd(&SetSpaceLayout {
+                index: space.index,
+                layout: *layout,
+            })
+            .with_context(|| format!("Cannot set the layout of space {:?}", space.index))?;
+        }
+    }
+
+    State::update(|state| state.space_layouts.extend(layouts))
+        .context("Cannot remember the space layouts")
+}
@@ -0,0 +1,88 @@
+use std::{
+    process::Command,
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+
+use crate::{
+    label::space::StableSpaceIndex,
+    window_criteria::WindowCriteria,
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::{FocusWindowById, QueryWindows},
+        transport::Window,
+    },
+};
+
+use super::{
+    focus_window_by_criteria::focus_window_by_criteria,
+    move_window_to_space::send_window_to_stable_index,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Focuses a window matching the criteria. When there is none, runs the launch command.
+///
+/// With a `wait_timeout`, waits for the launched window to appear, moves it to the space with
+/// `stable_index` (if any) and focuses it.
+pub fn raise_or_run(
+    criteria: &WindowCriteria,
+    launch_command: &str,
+    wait_timeout: Option<Duration>,
+    stable_index: Option<StableSpaceIndex>,
+) -> anyhow::Result<()> {
+    if query_matching_window(criteria)?.is_some() {
+        return focus_window_by_criteria(criteria);
+    }
+
+    log::info!("No window matches {criteria}. Running {launch_command:?}");
+    Command::new("sh")
+        .args(["-c", launch_command])
+        .spawn()
+        .with_context(|| format!("Cannot run {launch_command:?}"))?;
+
+    let Some(wait_timeout) = wait_timeout else {
+        return Ok(());
+    };
+
+    let window = wait_for_matching_window(criteria, wait_timeout)?.with_context(|| {
+        format!("No window matching {criteria} appeared within {wait_timeout:?}")
+    })?;
+    log::debug!("Window {:?} appeared", window.id);
+
+    if let Some(stable_index) = stable_index {
+        send_window_to_stable_index(window.id, stable_index)?;
+    }
+
+    execute_yabai_cmd(&FocusWindowById::new(window.id))
+        .with_context(|| format!("Cannot focus window {:?}", window.id))
+}
+
+fn wait_for_matching_window(
+    criteria: &WindowCriteria,
+    timeout: Duration,
+) -> anyhow::Result<Option<Window>> {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        if let Some(window) = query_matching_window(criteria)? {
+            return Ok(Some(window));
+        }
+
+        if Instant::now() >= deadline {
+            return Ok(None);
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn query_matching_window(criteria: &WindowCriteria) -> anyhow::Result<Option<Window>> {
+    let windows = execute_yabai_cmd(&QueryWindows)
+        .context("Could not query windows")?
+        .context("Could not parse windows")?;
+
+    Ok(windows.into_iter().find(|window| criteria.matches(window)))
+}
@@ -0,0 +1,103 @@
+use anyhow::Context;
+use log::{debug, info};
+
+use crate::{
+    config::Config,
+    label::{space::StableSpaceIndex, Labelable},
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::{QueryDisplays, QuerySpaces},
+        transport::{Display, Space},
+    },
+};
+
+use super::move_space::send_space_to_display;
+
+/// Sends each labeled space to the display it is assigned to in the config.
+///
+/// Spaces assigned to displays that are not connected stay where they are.
+pub fn rebalance_spaces(config: &Config) -> anyhow::Result<()> {
+    let (spaces, displays) = query_spaces_and_displays()?;
+
+    let spaces_to_send: Vec<_> = spaces
+        .iter()
+        .filter(|space| !space.is_native_fullscreen)
+        .filter_map(|space| {
+            let label = space.label.as_deref()?;
+            let stable_index = Space::parse_index(label).ok()?;
+            let display_uuid = config.assigned_display(stable_index)?;
+
+            let Some(target_display) = displays.iter().find(|display| display.uuid.eq_ignore_ascii_case(display_uuid))
+            else {
+                info!("Display {display_uuid} assigned to space {label:?} is not connected. Leaving the space on display {:?}", space.display_index);
+                return None;
+            };
+
+            (target_display.index != space.display_index)
+                .then(|| (stable_index, target_display.uuid.clone()))
+        })
+        .collect();
+
+    info!(
+        "Will send {} spaces to their assigned displays",
+        spaces_to_send.len()
+    );
+
+    for (stable_index, display_uuid) in spaces_to_send {
+        send_space_with_stable_index_to_display(stable_index, &display_uuid)?;
+    }
+
+    Ok(())
+}
+
+/// Sends the space with a given stable index to the display with a given UUID.
+///
+/// Creates an extra space on the source display if the sent space is the last one there.
+pub(crate) fn send_space_with_stable_index_to_display(
+    stable_index: StableSpaceIndex,
+    display_uuid: &str,
+) -> anyhow::Result<()> {
+    // NOTE: indexes of spaces and displays change after each move, so query them every time
+    let (spaces, displays) = query_spaces_and_displays()?;
+
+    let space = find_space_with_stable_index(&spaces, stable_index)
+        .with_context(|| format!("Cannot find space with stable index {stable_index:?}"))?;
+    let source_display = displays
+        .iter()
+        .find(|display| display.index == space.display_index)
+        .with_context(|| format!("Cannot find the display of space {:?}", space.index))?;
+    let target_display = displays
+        .iter()
+        .find(|display| display.uuid.eq_ignore_ascii_case(display_uuid))
+        .with_context(|| format!("Display {display_uuid} is not connected"))?;
+
+    debug!("Sending space with stable index {stable_index:?} to display {display_uuid}");
+    send_space_to_display(space, source_display, target_display, true)
+}
+
+fn find_space_with_stable_index(
+    spaces: &[Space],
+    stable_index: StableSpaceIndex,
+) -> Option<&Space> {
+    spaces.iter().find(|space| {
+        space
+            .label
+            .as_deref()
+            .and_then(|label| Space::parse_index(label).ok())
+            == Some(stable_index)
+    })
+}
+
+pub(crate) fn query_spaces_and_displays() -> anyhow::Result<(Vec<Space>, Vec<Display>)> {
+    let spaces = execute_yabai_cmd(&QuerySpaces {
+        only_current_display: false,
+    })
+    .context("Could not query spaces")?
+    .context("Could not parse spaces")?;
+
+    let displays = execute_yabai_cmd(&QueryDisplays)
+        .context("Could not query displays")?
+        .context("Could not parse displays")?;
+
+    Ok((spaces, displays))
+}
@@ -2,33 +2,56 @@ use anyhow::Context;
 use itertools::Itertools;
 
 use crate::{
-    label::{space::StableSpaceIndex, Labelable},
-    yabai::{self, cli::execute_yabai_cmd, transport::Space},
+    config::{DisplayOrder, PlaceholdersConfig, ReorderConfig, UnlabeledSpacesPolicy},
+    label::{
+        space::{ParseSpaceLabelError, StableSpaceIndex},
+        Labelable,
+    },
+    yabai::{
+        self,
+        cli::execute_yabai_cmd,
+        transport::{Display, Space},
+    },
 };
 
-pub fn reorder_spaces_by_stable_indexes() -> anyhow::Result<()> {
+use super::{
+    placeholder_spaces::clean_up_placeholder_spaces,
+    rebalance::{query_spaces_and_displays, send_space_with_stable_index_to_display},
+};
+
+/// Reorders spaces on each display by their stable indexes.
+///
+/// Placeholder spaces created while distributing spaces across displays are cleaned up according
+/// to `placeholders_config`.
+pub fn reorder_spaces_by_stable_indexes(
+    config: &ReorderConfig,
+    placeholders_config: &PlaceholdersConfig,
+) -> anyhow::Result<()> {
+    if config.across_displays {
+        distribute_spaces_across_displays(&config.display_order)
+            .context("Cannot distribute spaces across displays")?;
+        clean_up_placeholder_spaces(placeholders_config.cleanup)?;
+    }
+
     let spaces = execute_yabai_cmd(&yabai::command::QuerySpaces {
         only_current_display: false,
     })
     .context("Cannot query yabai spaces")?
     .context("Cannot parse yabai spaces")?;
 
-    let (labeled_spaces, space_index_parsing_errors): (Vec<_>, Vec<_>) = spaces
+    let (spaces_by_display, space_index_parsing_errors): (Vec<_>, Vec<_>) = spaces
         .into_iter()
-        .filter_map(|space| {
-            let label = space.label.as_deref()?;
-            let stable_index = Space::parse_index(label);
-
-            let label = label.to_owned();
-            Some(stable_index.map(|stable_index| {
-                (
-                    space,
-                    LabeledSpace {
-                        label,
-                        stable_index,
-                    },
-                )
-            }))
+        .group_by(|space| space.display_index)
+        .into_iter()
+        .map(|(_, spaces)| {
+            let spaces: Vec<_> = spaces.collect();
+            let first_space_index = spaces
+                .first()
+                .expect("Each group contains at least one space")
+                .index;
+
+            spaces_to_reorder(&spaces, config.unlabeled)
+                .map(|spaces_to_reorder| (first_space_index, spaces_to_reorder))
         })
         .partition_result();
     anyhow::ensure!(
@@ -36,119 +59,341 @@ pub fn reorder_spaces_by_stable_indexes() -> anyhow::Result<()> {
         "Spaces stable index cannot be parsed: {space_index_parsing_errors:?}",
     );
 
-    let spaces_by_display = labeled_spaces
-        .into_iter()
-        .group_by(|(space, _)| space.display_index);
-
-    for (_, spaces) in spaces_by_display.into_iter() {
-        let labeled_spaces: Vec<_> = spaces.map(|(_, labeled_spaces)| labeled_spaces).collect();
-        let move_list = generate_move_list(&labeled_spaces);
+    for (first_space_index, spaces) in spaces_by_display {
+        let move_list = generate_move_list(&spaces, |space| space.is_native_fullscreen);
+        let mut current_order = spaces;
 
         for m in move_list {
-            let move_command: yabai::command::MoveSpace = m.into();
+            let selector = |space: &ReorderedSpace| {
+                let position = current_order
+                    .iter()
+                    .position(|other| other == space)
+                    .expect("Moved spaces exist on the display");
+
+                space.selector(*first_space_index + position as u32)
+            };
+            let move_command = yabai::command::MoveSpace {
+                source_label: selector(&m.source),
+                target_label: selector(&m.target),
+            };
+
             execute_yabai_cmd(&move_command).with_context(|| {
                 format!(
-                    "Cannot move space {:?} before space {:?}",
+                    "Cannot move space {:?} to the position of space {:?}",
                     move_command.source_label, move_command.target_label
                 )
             })?;
+
+            apply_move(&mut current_order, &m);
         }
     }
 
     Ok(())
 }
 
-#[derive(Clone, Eq)]
-struct LabeledSpace {
-    stable_index: StableSpaceIndex,
-    label: String,
+/// Spaces on a single display that take part in reordering, in their current order.
+fn spaces_to_reorder(
+    spaces: &[Space],
+    unlabeled_policy: UnlabeledSpacesPolicy,
+) -> Result<Vec<ReorderedSpace>, ParseSpaceLabelError> {
+    spaces
+        .iter()
+        .enumerate()
+        .filter_map(|(position, space)| {
+            let sort_key = match (space.label.as_deref(), unlabeled_policy) {
+                (Some(label), _) => match Space::parse_index(label) {
+                    Ok(stable_index) => SpaceSortKey::Labeled(stable_index, position),
+                    Err(error) => return Some(Err(error)),
+                },
+                (None, _) if space.is_native_fullscreen => SpaceSortKey::NativeFullscreen(position),
+                (None, UnlabeledSpacesPolicy::Keep) => return None,
+                (None, UnlabeledSpacesPolicy::First) => SpaceSortKey::UnlabeledFirst(position),
+                (None, UnlabeledSpacesPolicy::Last) => SpaceSortKey::UnlabeledLast(position),
+            };
+
+            Some(Ok(ReorderedSpace {
+                sort_key,
+                label: space.label.clone(),
+                is_native_fullscreen: space.is_native_fullscreen,
+            }))
+        })
+        .collect()
+}
+
+/// Sends spaces between displays so that lower stable indexes are on displays that come first in
+/// `display_order`. The number of labeled spaces on each display stays the same.
+fn distribute_spaces_across_displays(display_order: &DisplayOrder) -> anyhow::Result<()> {
+    let (spaces, displays) = query_spaces_and_displays()?;
+
+    let stable_indexes_by_display: Vec<_> = sort_displays(&displays, display_order)
+        .into_iter()
+        .map(|display| {
+            let stable_indexes: Vec<_> = spaces
+                .iter()
+                .filter(|space| space.display_index == display.index && !space.is_native_fullscreen)
+                .filter_map(|space| Space::parse_index(space.label.as_deref()?).ok())
+                .collect();
+
+            (display.uuid.as_str(), stable_indexes)
+        })
+        .collect();
+
+    let spaces_to_send: Vec<_> = assign_stable_indexes_to_displays(&stable_indexes_by_display)
+        .into_iter()
+        .filter(|(stable_index, display_uuid)| {
+            !stable_indexes_by_display
+                .iter()
+                .any(|(uuid, stable_indexes)| {
+                    uuid == display_uuid && stable_indexes.contains(stable_index)
+                })
+        })
+        .collect();
+
+    log::info!(
+        "Will send {} spaces to other displays",
+        spaces_to_send.len()
+    );
+
+    for (stable_index, display_uuid) in spaces_to_send {
+        send_space_with_stable_index_to_display(stable_index, display_uuid)?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn sort_displays<'a>(
+    displays: &'a [Display],
+    display_order: &DisplayOrder,
+) -> Vec<&'a Display> {
+    let left_to_right = |display_a: &&Display, display_b: &&Display| {
+        f32::total_cmp(&display_a.frame.x, &display_b.frame.x)
+            .then(f32::total_cmp(&display_a.frame.y, &display_b.frame.y))
+    };
+
+    match display_order {
+        DisplayOrder::LeftToRight => displays.iter().sorted_by(left_to_right).collect(),
+        DisplayOrder::Uuids(uuids) => displays
+            .iter()
+            .sorted_by(|display_a, display_b| {
+                let position = |display: &Display| {
+                    uuids
+                        .iter()
+                        .position(|uuid| uuid.eq_ignore_ascii_case(&display.uuid))
+                        .unwrap_or(uuids.len())
+                };
+
+                position(display_a)
+                    .cmp(&position(display_b))
+                    .then_with(|| left_to_right(display_a, display_b))
+            })
+            .collect(),
+    }
+}
+
+/// Assigns the lowest stable indexes to the first display, keeping the number of spaces on each
+/// display the same.
+fn assign_stable_indexes_to_displays<D: Copy>(
+    stable_indexes_by_display: &[(D, Vec<StableSpaceIndex>)],
+) -> Vec<(StableSpaceIndex, D)> {
+    let mut sorted_stable_indexes = stable_indexes_by_display
+        .iter()
+        .flat_map(|(_, stable_indexes)| stable_indexes.iter().copied())
+        .sorted();
+
+    stable_indexes_by_display
+        .iter()
+        .flat_map(|(display, stable_indexes)| {
+            sorted_stable_indexes
+                .by_ref()
+                .take(stable_indexes.len())
+                .map(|stable_index| (stable_index, *display))
+                .collect::<Vec<_>>()
+        })
+        .collect()
 }
 
-impl PartialEq for LabeledSpace {
+/// Spaces are sorted by the variant first, and then by the value inside of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SpaceSortKey {
+    /// Position of the unlabeled space on the display before reordering.
+    UnlabeledFirst(usize),
+    /// Stable index, then the position on the display before reordering. Spaces with the same
+    /// stable index keep their relative order.
+    Labeled(StableSpaceIndex, usize),
+    /// Position of the unlabeled space on the display before reordering.
+    UnlabeledLast(usize),
+    /// Position of the native fullscreen space on the display before reordering.
+    NativeFullscreen(usize),
+}
+
+#[derive(Debug, Clone, Eq)]
+struct ReorderedSpace {
+    sort_key: SpaceSortKey,
+    label: Option<String>,
+    /// Yabai cannot move native fullscreen spaces. Spaces between them are sorted separately.
+    is_native_fullscreen: bool,
+}
+
+impl ReorderedSpace {
+    /// Labeled spaces are selected by their label, which does not change when spaces move.
+    /// Other spaces are selected by their current mission control `index`.
+    fn selector(&self, index: u32) -> String {
+        match &self.label {
+            Some(label) => label.clone(),
+            None => index.to_string(),
+        }
+    }
+}
+
+impl PartialEq for ReorderedSpace {
     fn eq(&self, other: &Self) -> bool {
-        self.stable_index.eq(&other.stable_index)
+        self.sort_key.eq(&other.sort_key)
     }
 }
 
-impl PartialOrd for LabeledSpace {
+impl PartialOrd for ReorderedSpace {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Ord for LabeledSpace {
+impl Ord for ReorderedSpace {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.stable_index.cmp(&other.stable_index)
+        self.sort_key.cmp(&other.sort_key)
     }
 }
 
-/// An instruction to move `source` BEFORE `target`.
+/// An instruction to move `source` to the position currently occupied by `target`.
+/// Items in between shift by one position towards the previous position of `source`.
+///
 /// This is how yabai's space `--move` command works.
 struct Move<Item> {
     source: Item,
     target: Item,
 }
 
-impl From<Move<LabeledSpace>> for yabai::command::MoveSpace {
-    fn from(value: Move<LabeledSpace>) -> Self {
-        Self {
-            source_label: value.source.label,
-            target_label: value.target.label,
-        }
-    }
+fn apply_move<Item: Eq>(items: &mut Vec<Item>, m: &Move<Item>) {
+    let source_index = items
+        .iter()
+        .position(|item| item == &m.source)
+        .expect("Move source must exist in items");
+    let target_index = items
+        .iter()
+        .position(|item| item == &m.target)
+        .expect("Move target must exist in items");
+
+    let item = items.remove(source_index);
+    items.insert(target_index, item);
 }
 
-fn generate_move_list<Item: Eq + Ord + Clone>(items: &[Item]) -> Vec<Move<Item>> {
-    if items.is_empty() {
-        return Vec::new();
-    }
+/// Generates the list of moves that sorts `items`, without moving pinned items.
+///
+/// Pinned items keep their positions. The items between them are sorted separately.
+fn generate_move_list<Item: Eq + Ord + Clone>(
+    items: &[Item],
+    is_pinned: impl Fn(&Item) -> bool,
+) -> Vec<Move<Item>> {
+    items
+        .split(is_pinned)
+        .flat_map(generate_segment_move_list)
+        .collect()
+}
 
-    let mut greatest_encountered = &items[0];
+/// Generates the minimal list of moves that sorts `items`.
+///
+/// Items in the longest increasing subsequence stay in place. Every other item is moved once, next
+/// to the items that were already put in order.
+fn generate_segment_move_list<Item: Eq + Ord + Clone>(items: &[Item]) -> Vec<Move<Item>> {
+    let mut current_order: Vec<&Item> = items.iter().collect();
+    let mut ordered = longest_increasing_subsequence(items);
     let mut moves: Vec<Move<Item>> = Vec::new();
 
-    for (index, item) in items.iter().enumerate() {
-        let greatest_item_so_far = item >= greatest_encountered;
-        greatest_encountered = greatest_encountered.max(item);
-        if greatest_item_so_far {
+    for item in items {
+        let position = position_of(&current_order, item);
+        if ordered[position] {
             continue;
         }
 
-        let target = items[0..index]
-            .iter()
-            .filter(|other| *other > item)
-            .min()
-            .expect(
-                "The currently processed item is not the greatest one among the preceding items",
-            );
-
+        let ordered_items = || {
+            current_order
+                .iter()
+                .zip(ordered.iter())
+                .filter_map(|(other, &is_ordered)| is_ordered.then_some(*other))
+        };
+        let successor = ordered_items().filter(|other| *other > item).min();
+        let predecessor = ordered_items().filter(|other| *other < item).max();
+
+        let target = match (predecessor, successor) {
+            (_, Some(successor)) if position > position_of(&current_order, successor) => successor,
+            (Some(predecessor), _) if position < position_of(&current_order, predecessor) => {
+                predecessor
+            }
+            _ => unreachable!(
+                "An item outside of the longest increasing subsequence cannot already be in order"
+            ),
+        };
+
+        let target_position = position_of(&current_order, target);
         moves.push(Move {
             source: item.clone(),
             target: target.clone(),
-        })
+        });
+
+        let moved_item = current_order.remove(position);
+        ordered.remove(position);
+        current_order.insert(target_position, moved_item);
+        ordered.insert(target_position, true);
     }
 
     moves
 }
 
+fn position_of<Item: Eq>(items: &[&Item], item: &Item) -> usize {
+    items
+        .iter()
+        .position(|other| *other == item)
+        .expect("The item must exist in the list")
+}
+
+/// Returns a mask of items that belong to the longest (strictly) increasing subsequence.
+fn longest_increasing_subsequence<Item: Ord>(items: &[Item]) -> Vec<bool> {
+    // NOTE: there are at most a few spaces per display, so the quadratic algorithm is fine
+    let mut lengths: Vec<usize> = vec![1; items.len()];
+    let mut previous: Vec<Option<usize>> = vec![None; items.len()];
+
+    for index in 0..items.len() {
+        for other_index in 0..index {
+            if items[other_index] < items[index] && lengths[other_index] + 1 > lengths[index] {
+                lengths[index] = lengths[other_index] + 1;
+                previous[index] = Some(other_index);
+            }
+        }
+    }
+
+    let mut in_subsequence = vec![false; items.len()];
+    let mut current = lengths
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, length)| **length)
+        .map(|(index, _)| index);
+
+    while let Some(index) = current {
+        in_subsequence[index] = true;
+        current = previous[index];
+    }
+
+    in_subsequence
+}
+
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     fn simulate_moves<Item: Eq>(items: &mut Vec<Item>, moves: &[Move<Item>]) {
         for m in moves {
-            let source_index = items
-                .iter()
-                .position(|item| item == &m.source)
-                .expect("Move source must exist in items");
-
-            let item = items.remove(source_index);
-
-            let target_index = items
-                .iter()
-                .position(|item| item == &m.target)
-                .expect("Move target must exist in items");
-
-            items.insert(target_index, item);
+            apply_move(items, m);
         }
     }
 
@@ -160,7 +405,7 @@ mod tests {
 
     impl GenerateMoveListTestCase {
         fn run(mut self) {
-            let moves = generate_move_list(&self.items);
+            let moves = generate_move_list(&self.items, |_| false);
             simulate_moves(&mut self.items, &moves);
 
             assert_eq!(self.expected, self.items);
@@ -196,7 +441,7 @@ mod tests {
             items: vec![5, 2, 1, 4, 3],
             expected: vec![1, 2, 3, 4, 5],
 
-            expected_moves_count: 4,
+            expected_moves_count: 3,
         }
         .run();
     }
@@ -211,4 +456,300 @@ mod tests {
         }
         .run();
     }
+
+    fn stable_indexes(indexes: &[u32]) -> Vec<StableSpaceIndex> {
+        indexes
+            .iter()
+            .map(|index| StableSpaceIndex::try_from(*index).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn assigns_lowest_stable_indexes_to_first_displays() {
+        let assignments = assign_stable_indexes_to_displays(&[
+            ("first", stable_indexes(&[4, 1])),
+            ("second", stable_indexes(&[2])),
+            ("third", stable_indexes(&[])),
+            ("fourth", stable_indexes(&[5, 3])),
+        ]);
+
+        assert_eq!(
+            vec![
+                (StableSpaceIndex::try_from(1).unwrap(), "first"),
+                (StableSpaceIndex::try_from(2).unwrap(), "first"),
+                (StableSpaceIndex::try_from(3).unwrap(), "second"),
+                (StableSpaceIndex::try_from(4).unwrap(), "fourth"),
+                (StableSpaceIndex::try_from(5).unwrap(), "fourth"),
+            ],
+            assignments
+        );
+    }
+
+    fn display(uuid: &str, x: f32, y: f32) -> Display {
+        let frame = yabai::transport::Frame {
+            x,
+            y,
+            width: 100.0,
+            height: 100.0,
+        };
+
+        Display {
+            uuid: uuid.to_owned(),
+            ..yabai::transport::fixtures::display(0, frame)
+        }
+    }
+
+    #[test]
+    fn sorts_displays() {
+        let displays = vec![
+            display("right", 100.0, 0.0),
+            display("bottom-left", 0.0, 100.0),
+            display("top-left", 0.0, 0.0),
+        ];
+        let uuids = |displays: Vec<&Display>| -> Vec<String> {
+            displays
+                .into_iter()
+                .map(|display| display.uuid.clone())
+                .collect()
+        };
+
+        assert_eq!(
+            vec!["top-left", "bottom-left", "right"],
+            uuids(sort_displays(&displays, &DisplayOrder::LeftToRight))
+        );
+        assert_eq!(
+            vec!["right", "top-left", "bottom-left"],
+            uuids(sort_displays(
+                &displays,
+                &DisplayOrder::Uuids(vec!["right".to_owned()])
+            ))
+        );
+    }
+
+    fn space(index: u32, label: &str, is_native_fullscreen: bool) -> Space {
+        Space {
+            is_native_fullscreen,
+            ..yabai::transport::fixtures::space(index, (!label.is_empty()).then_some(label))
+        }
+    }
+
+    fn reorder(spaces: &[Space], unlabeled_policy: UnlabeledSpacesPolicy) -> Vec<SpaceSortKey> {
+        let mut spaces = spaces_to_reorder(spaces, unlabeled_policy).unwrap();
+        let moves = generate_move_list(&spaces, |space| space.is_native_fullscreen);
+        simulate_moves(&mut spaces, &moves);
+
+        spaces.into_iter().map(|space| space.sort_key).collect()
+    }
+
+    #[test]
+    fn reorders_unlabeled_spaces() {
+        let spaces = [
+            space(1, "", true),
+            space(2, "2:", false),
+            space(3, "", false),
+            space(4, "1:", false),
+        ];
+        let first = StableSpaceIndex::try_from(1).unwrap();
+        let second = StableSpaceIndex::try_from(2).unwrap();
+
+        assert_eq!(
+            vec![
+                SpaceSortKey::NativeFullscreen(0),
+                SpaceSortKey::UnlabeledFirst(2),
+                SpaceSortKey::Labeled(first, 3),
+                SpaceSortKey::Labeled(second, 1),
+            ],
+            reorder(&spaces, UnlabeledSpacesPolicy::First)
+        );
+        assert_eq!(
+            vec![
+                SpaceSortKey::NativeFullscreen(0),
+                SpaceSortKey::Labeled(first, 3),
+                SpaceSortKey::Labeled(second, 1),
+                SpaceSortKey::UnlabeledLast(2),
+            ],
+            reorder(&spaces, UnlabeledSpacesPolicy::Last)
+        );
+        assert_eq!(
+            vec![
+                SpaceSortKey::NativeFullscreen(0),
+                SpaceSortKey::Labeled(first, 3),
+                SpaceSortKey::Labeled(second, 1)
+            ],
+            reorder(&spaces, UnlabeledSpacesPolicy::Keep)
+        );
+    }
+
+    #[test]
+    fn keeps_native_fullscreen_spaces_in_place_with_the_keep_policy() {
+        let spaces = [
+            space(1, "2:", false),
+            space(2, "", true),
+            space(3, "1:", false),
+        ];
+        let first = StableSpaceIndex::try_from(1).unwrap();
+        let second = StableSpaceIndex::try_from(2).unwrap();
+
+        assert_eq!(
+            vec![
+                SpaceSortKey::Labeled(second, 0),
+                SpaceSortKey::NativeFullscreen(1),
+                SpaceSortKey::Labeled(first, 2),
+            ],
+            reorder(&spaces, UnlabeledSpacesPolicy::Keep)
+        );
+    }
+
+    #[test]
+    fn keeps_the_order_of_duplicate_stable_indexes() {
+        let spaces = [
+            space(1, "3: Mail", false),
+            space(2, "1:", false),
+            space(3, "3:", false),
+        ];
+        let first = StableSpaceIndex::try_from(1).unwrap();
+        let third = StableSpaceIndex::try_from(3).unwrap();
+
+        assert_eq!(
+            vec![
+                SpaceSortKey::Labeled(first, 1),
+                SpaceSortKey::Labeled(third, 0),
+                SpaceSortKey::Labeled(third, 2),
+            ],
+            reorder(&spaces, UnlabeledSpacesPolicy::Keep)
+        );
+    }
+
+    #[test]
+    fn selects_unlabeled_spaces_by_index() {
+        let spaces = spaces_to_reorder(
+            &[space(3, "", false), space(4, "1: Web", false)],
+            UnlabeledSpacesPolicy::Last,
+        )
+        .unwrap();
+
+        assert_eq!("3", spaces[0].selector(3));
+        assert_eq!("1: Web", spaces[1].selector(4));
+    }
+
+    #[test]
+    fn single_move_of_the_smallest_item() {
+        GenerateMoveListTestCase {
+            items: vec![2, 3, 4, 5, 1],
+            expected: vec![1, 2, 3, 4, 5],
+
+            expected_moves_count: 1,
+        }
+        .run();
+    }
+
+    #[test]
+    fn single_move_of_the_greatest_item() {
+        GenerateMoveListTestCase {
+            items: vec![5, 1, 2, 3, 4],
+            expected: vec![1, 2, 3, 4, 5],
+
+            expected_moves_count: 1,
+        }
+        .run();
+    }
+
+    /// Patience sorting, independent of the implementation under test.
+    fn longest_increasing_subsequence_length(items: &[u32]) -> usize {
+        let mut piles_tops: Vec<u32> = Vec::new();
+
+        for &item in items {
+            let pile = piles_tops.partition_point(|top| *top < item);
+            if pile == piles_tops.len() {
+                piles_tops.push(item);
+            } else {
+                piles_tops[pile] = item;
+            }
+        }
+
+        piles_tops.len()
+    }
+
+    fn shuffled_items() -> impl Strategy<Value = Vec<u32>> {
+        (0..12u32).prop_flat_map(|len| Just((1..=len).collect::<Vec<_>>()).prop_shuffle())
+    }
+
+    /// Items with repeated values, made unique by their position like labeled spaces are.
+    fn items_with_duplicates() -> impl Strategy<Value = Vec<(u32, usize)>> {
+        proptest::collection::vec(1..5u32, 0..12).prop_map(|values| {
+            values
+                .into_iter()
+                .enumerate()
+                .map(|(position, value)| (value, position))
+                .collect()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn moves_sort_items_with_duplicates(mut items in items_with_duplicates()) {
+            let moves = generate_move_list(&items, |_| false);
+            simulate_moves(&mut items, &moves);
+
+            prop_assert!(items.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+
+        #[test]
+        fn moves_sort_items(mut items in shuffled_items()) {
+            let moves = generate_move_list(&items, |_| false);
+            simulate_moves(&mut items, &moves);
+
+            prop_assert!(items.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+
+        #[test]
+        fn moves_count_is_minimal(items in shuffled_items()) {
+            let moves = generate_move_list(&items, |_| false);
+
+            prop_assert_eq!(
+                items.len() - longest_increasing_subsequence_length(&items),
+                moves.len()
+            );
+        }
+
+        #[test]
+        fn moves_do_not_move_pinned_items(
+            (mut items, pinned_mask) in shuffled_items().prop_flat_map(|items| {
+                let len = items.len();
+                (Just(items), proptest::collection::vec(any::<bool>(), len))
+            })
+        ) {
+            let pinned: Vec<u32> = items
+                .iter()
+                .zip(&pinned_mask)
+                .filter_map(|(item, is_pinned)| is_pinned.then_some(*item))
+                .collect();
+
+            let moves = generate_move_list(&items, |item| pinned.contains(item));
+            prop_assert!(moves.iter().all(|m| !pinned.contains(&m.source)));
+            prop_assert!(moves.iter().all(|m| !pinned.contains(&m.target)));
+
+            let original_items = items.clone();
+            simulate_moves(&mut items, &moves);
+            for (position, is_pinned) in pinned_mask.iter().enumerate() {
+                if *is_pinned {
+                    prop_assert_eq!(original_items[position], items[position]);
+                }
+            }
+            prop_assert!(items
+                .split(|item| pinned.contains(item))
+                .all(|segment| segment.windows(2).all(|pair| pair[0] < pair[1])));
+        }
+
+        #[test]
+        fn longest_increasing_subsequence_is_increasing(items in shuffled_items()) {
+            let subsequence: Vec<_> = items
+                .iter()
+                .zip(longest_increasing_subsequence(&items))
+                .filter_map(|(item, in_subsequence)| in_subsequence.then_some(*item))
+                .collect();
+
+            prop_assert!(subsequence.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
 }
@@ -0,0 +1,234 @@
+use std::{num::ParseFloatError, str::FromStr};
+
+use anyhow::Context;
+
+use crate::{
+    position::CardinalDirection,
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::{QueryDisplays, QueryWindows, ResizeHandle, ResizeWindow},
+        transport::Frame,
+    },
+};
+
+/// Maximum distance between the window edge and the display edge for the window to be considered
+/// at the display boundary.
+///
+/// Accounts for the menu bar, yabai's padding and window gaps.
+const DISPLAY_EDGE_TOLERANCE: f32 = 50.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResizeAmount {
+    Pixels(f32),
+    /// Percentage of the display width or height, depending on the direction.
+    Percent(f32),
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ParseResizeAmountError {
+    #[error("Cannot parse number")]
+    ParseFloatError(#[from] ParseFloatError),
+
+    #[error("Amount must be positive")]
+    NotPositive,
+}
+
+impl FromStr for ResizeAmount {
+    type Err = ParseResizeAmountError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let amount = if let Some(percent) = s.strip_suffix('%') {
+            Self::Percent(percent.trim().parse()?)
+        } else {
+            Self::Pixels(s.strip_suffix("px").unwrap_or(s).trim().parse()?)
+        };
+
+        match amount {
+            Self::Pixels(value) | Self::Percent(value) if value > 0.0 => Ok(amount),
+            _ => Err(ParseResizeAmountError::NotPositive),
+        }
+    }
+}
+
+impl ResizeAmount {
+    fn to_pixels(self, display_frame: &Frame, direction: CardinalDirection) -> f32 {
+        match self {
+            ResizeAmount::Pixels(pixels) => pixels,
+            ResizeAmount::Percent(percent) => {
+                let display_size = match direction {
+                    CardinalDirection::West | CardinalDirection::East => display_frame.width,
+                    CardinalDirection::North | CardinalDirection::South => display_frame.height,
+                };
+
+                display_size * percent / 100.0
+            }
+        }
+    }
+}
+
+/// Grows the focused window in a given direction.
+///
+/// When the window already touches the display edge in that direction, the opposite edge is moved
+/// instead, shrinking the window.
+pub fn resize_window_in_direction(
+    direction: CardinalDirection,
+    amount: ResizeAmount,
+) -> anyhow::Result<()> {
+    let windows = execute_yabai_cmd(&QueryWindows)
+        .context("Could not query windows")?
+        .context("Could not parse windows")?;
+
+    let active_window = windows
+        .iter()
+        .find(|window| window.has_focus)
+        .context("No window has focus")?;
+
+    let displays = execute_yabai_cmd(&QueryDisplays)
+        .context("Could not query displays")?
+        .context("Could not parse displays")?;
+
+    let display = displays
+        .iter()
+        .find(|display| display.index == active_window.display_index)
+        .with_context(|| {
+            format!(
+                "Could not find the display of the active window {:?}",
+                active_window.id
+            )
+        })?;
+
+    let resize_command = get_resize_command(
+        &active_window.frame,
+        &display.frame,
+        direction,
+        amount.to_pixels(&display.frame, direction),
+    );
+
+    log::info!(
+        "Resizing window {:?} by moving its {} edge by ({}, {})",
+        active_window.id,
+        resize_command.handle,
+        resize_command.dx,
+        resize_command.dy
+    );
+
+    execute_yabai_cmd(&resize_command)
+        .with_context(|| format!("Could not resize window {:?}", active_window.id))
+}
+
+fn get_resize_command(
+    window_frame: &Frame,
+    display_frame: &Frame,
+    direction: CardinalDirection,
+    amount: f32,
+) -> ResizeWindow {
+    let amount = amount.round() as i32;
+
+    let (handle, opposite_handle, dx, dy) = match direction {
+        CardinalDirection::North => (ResizeHandle::Top, ResizeHandle::Bottom, 0, -amount),
+        CardinalDirection::South => (ResizeHandle::Bottom, ResizeHandle::Top, 0, amount),
+        CardinalDirection::West => (ResizeHandle::Left, ResizeHandle::Right, -amount, 0),
+        CardinalDirection::East => (ResizeHandle::Right, ResizeHandle::Left, amount, 0),
+    };
+
+    let distance_to_display_edge = match direction {
+        CardinalDirection::North => window_frame.y - display_frame.y,
+        CardinalDirection::South => {
+            (display_frame.y + display_frame.height) - (window_frame.y + window_frame.height)
+        }
+        CardinalDirection::West => window_frame.x - display_frame.x,
+        CardinalDirection::East => {
+            (display_frame.x + display_frame.width) - (window_frame.x + window_frame.width)
+        }
+    };
+
+    let handle = if distance_to_display_edge <= DISPLAY_EDGE_TOLERANCE {
+        log::debug!("The window is at the display edge in direction {direction:?}. Moving the opposite edge");
+        opposite_handle
+    } else {
+        handle
+    };
+
+    ResizeWindow { handle, dx, dy }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_resize_amount() {
+        assert_eq!(Ok(ResizeAmount::Pixels(20.0)), "20".parse());
+        assert_eq!(Ok(ResizeAmount::Pixels(20.0)), "20px".parse());
+        assert_eq!(Ok(ResizeAmount::Percent(5.5)), "5.5%".parse());
+        assert_eq!(
+            Err(ParseResizeAmountError::NotPositive),
+            "-5%".parse::<ResizeAmount>()
+        );
+        assert!(matches!(
+            "five".parse::<ResizeAmount>(),
+            Err(ParseResizeAmountError::ParseFloatError(..))
+        ));
+    }
+
+    const DISPLAY_FRAME: Frame = Frame {
+        x: 0.0,
+        y: 0.0,
+        width: 1000.0,
+        height: 800.0,
+    };
+
+    /// The left half of the display.
+    const LEFT_WINDOW_FRAME: Frame = Frame {
+        x: 10.0,
+        y: 35.0,
+        width: 485.0,
+        height: 755.0,
+    };
+
+    #[test]
+    fn grows_window_towards_the_direction() {
+        let command = get_resize_command(
+            &LEFT_WINDOW_FRAME,
+            &DISPLAY_FRAME,
+            CardinalDirection::East,
+            20.0,
+        );
+
+        assert_eq!(ResizeHandle::Right, command.handle);
+        assert_eq!((20, 0), (command.dx, command.dy));
+    }
+
+    #[test]
+    fn moves_opposite_edge_at_display_boundary() {
+        let command = get_resize_command(
+            &LEFT_WINDOW_FRAME,
+            &DISPLAY_FRAME,
+            CardinalDirection::West,
+            20.0,
+        );
+        assert_eq!(ResizeHandle::Right, command.handle);
+        assert_eq!((-20, 0), (command.dx, command.dy));
+
+        let command = get_resize_command(
+            &LEFT_WINDOW_FRAME,
+            &DISPLAY_FRAME,
+            CardinalDirection::North,
+            20.0,
+        );
+        assert_eq!(ResizeHandle::Bottom, command.handle);
+        assert_eq!((0, -20), (command.dx, command.dy));
+    }
+
+    #[test]
+    fn converts_percent_to_pixels() {
+        assert_eq!(
+            100.0,
+            ResizeAmount::Percent(10.0).to_pixels(&DISPLAY_FRAME, CardinalDirection::West)
+        );
+        assert_eq!(
+            80.0,
+            ResizeAmount::Percent(10.0).to_pixels(&DISPLAY_FRAME, CardinalDirection::South)
+        );
+    }
+}
@@ -2,7 +2,10 @@ use anyhow::Context;
 use clap::Args;
 
 use crate::{
-    label::{space::StableSpaceIndex, Labelable},
+    label::{
+        space::{apply_remembered_layout, StableSpaceIndex},
+        Labelable,
+    },
     yabai::{self, cli::execute_yabai_cmd, command::QuerySpaces, transport::Space},
 };
 
@@ -28,6 +31,12 @@ pub fn set_space_label(args: SetSpaceLabelArgs) -> anyhow::Result<()> {
         .first()
         .context("Active space cannot be found")?;
 
+    anyhow::ensure!(
+        !active_space.is_native_fullscreen,
+        "Cannot label the native fullscreen space {:?}",
+        active_space.index
+    );
+
     let stable_index = {
         if let Some(stable_index) = args.stable_index {
             let existing_space_with_same_stable_index = inactive_spaces.iter().find(|space| {
@@ -62,5 +71,13 @@ pub fn set_space_label(args: SetSpaceLabelArgs) -> anyhow::Result<()> {
         )
     })?;
 
+    let previous_stable_index = active_space
+        .label
+        .as_deref()
+        .and_then(|label| Space::parse_index(label).ok());
+    if previous_stable_index != Some(stable_index) {
+        apply_remembered_layout(active_space.index, stable_index);
+    }
+
     Ok(())
 }
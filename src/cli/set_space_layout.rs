@@ -0,0 +1,111 @@
+use anyhow::Context;
+use clap::{Subcommand, ValueEnum};
+
+use crate::{
+    label::{space::StableSpaceIndex, Labelable},
+    state::State,
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::{QuerySpaces, SetSpaceLayout, ToggleWindow, WindowToggle},
+        transport::{Space, SpaceType},
+    },
+};
+
+#[derive(Subcommand, Clone, Copy, Debug)]
+pub enum LayoutCommand {
+    /// Tile windows using binary space partitioning.
+    Bsp,
+    /// Stack all windows on top of each other, like i3's tabbed layout.
+    Stack,
+    /// Let windows float.
+    Float,
+    /// Toggle between the bsp and stack layouts.
+    Toggle {
+        /// Toggle the split direction of the active window instead (horizontal or vertical).
+        what: Option<ToggleTarget>,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ToggleTarget {
+    Split,
+}
+
+/// Changes the layout of the focused space or the space with a given stable index.
+///
+/// The layout of spaces with a stable index is remembered and reapplied when the space is created
+/// again.
+pub fn set_space_layout(
+    command: LayoutCommand,
+    stable_index: Option<StableSpaceIndex>,
+) -> anyhow::Result<()> {
+    let spaces = execute_yabai_cmd(&QuerySpaces {
+        only_current_display: false,
+    })
+    .context("Could not query spaces")?
+    .context("Could not parse spaces")?;
+
+    let space = match stable_index {
+        Some(stable_index) => spaces
+            .iter()
+            .find(|space| space_stable_index(space) == Some(stable_index))
+            .with_context(|| format!("Cannot find space with stable index {stable_index:?}"))?,
+        None => spaces
+            .iter()
+            .find(|space| space.has_focus)
+            .context("No space has focus")?,
+    };
+
+    let layout = match command {
+        LayoutCommand::Bsp => SpaceType::BSP,
+        LayoutCommand::Stack => SpaceType::Stack,
+        LayoutCommand::Float => SpaceType::Float,
+        LayoutCommand::Toggle {
+            what: Some(ToggleTarget::Split),
+        } => return toggle_split(space),
+        LayoutCommand::Toggle { what: None } => match space.r#type {
+            SpaceType::BSP => SpaceType::Stack,
+            SpaceType::Stack | SpaceType::Float => SpaceType::BSP,
+        },
+    };
+
+    log::info!("Setting the layout of space {:?} to {layout}", space.index);
+    execute_yabai_cmd(&SetSpaceLayout {
+        index: space.index,
+        layout,
+    })
+    .with_context(|| format!("Cannot set the layout of space {:?}", space.index))?;
+
+    if let Some(stable_index) = space_stable_index(space) {
+        log::debug!("Remembering layout {layout} for stable index {stable_index:?}");
+        State::update(|state| state.space_layouts.insert(stable_index, layout))
+            .context("Cannot remember the space layout")?;
+    }
+
+    Ok(())
+}
+
+fn toggle_split(space: &Space) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        space.has_focus,
+        "The split can only be toggled in the focused space"
+    );
+    anyhow::ensure!(
+        space.r#type == SpaceType::BSP,
+        "The split can only be toggled in a {} space. The space {:?} is {}",
+        SpaceType::BSP,
+        space.index,
+        space.r#type
+    );
+
+    log::info!("Toggling the split of the active window");
+    execute_yabai_cmd(&ToggleWindow {
+        window_id: None,
+        toggle: WindowToggle::Split,
+    })
+    .context("Cannot toggle the split of the active window")
+}
+
+fn space_stable_index(space: &Space) -> Option<StableSpaceIndex> {
+    Space::parse_index(space.label.as_deref()?).ok()
+}
@@ -0,0 +1,111 @@
+use anyhow::Context;
+
+use crate::{
+    config::FocusConfig,
+    position::{get_element_to_focus, Direction},
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::{
+            FocusWindowById, MoveWindowToSpace, QuerySpaceByIndex, QueryWindows, StackWindow,
+            ToggleWindow, WindowToggle,
+        },
+        transport::SpaceType,
+    },
+};
+
+use super::move_window_in_direction::{introspect_windows, IntrospectedWindows};
+
+/// Stacks the active window onto the closest window in a given direction, like moving a window
+/// into i3's tabbed container.
+///
+/// If the target window is in another space, the active window is moved there first.
+pub fn stack_window_in_direction(
+    direction: Direction,
+    focus_config: &FocusConfig,
+) -> anyhow::Result<()> {
+    let IntrospectedWindows {
+        active_window,
+        other_visible_windows,
+    } = introspect_windows()?;
+
+    let target_window = get_element_to_focus(
+        &active_window.frame,
+        &other_visible_windows,
+        direction,
+        focus_config.scoring,
+    )
+    .with_context(|| format!("No window in direction {direction:?} to stack onto"))?;
+    log::trace!("The closest window in direction {direction:?} is {target_window:?}");
+
+    if target_window.space_index != active_window.space_index {
+        let target_space = target_window.space_index.to_string();
+        log::info!("The target window is in another space ({target_space}). Moving the active window there first");
+
+        execute_yabai_cmd(&MoveWindowToSpace {
+            window_id: Some(active_window.id),
+            target_space_label: target_space.clone(),
+        })
+        .with_context(|| format!("Cannot move the active window to space {target_space}"))?;
+    }
+
+    log::info!(
+        "Stacking window {:?} onto window {:?}",
+        active_window.id,
+        target_window.id
+    );
+    execute_yabai_cmd(&StackWindow {
+        window_id: active_window.id,
+        target_window_id: target_window.id,
+    })
+    .with_context(|| {
+        format!(
+            "Cannot stack window {:?} onto window {:?}",
+            active_window.id, target_window.id
+        )
+    })?;
+
+    log::info!("Focusing the stacked window {:?}", active_window.id);
+    execute_yabai_cmd(&FocusWindowById::new(active_window.id))
+        .with_context(|| format!("Cannot focus window {:?}", active_window.id))
+}
+
+/// Takes the active window out of its stack and puts it back into the BSP tree.
+///
+/// Fails in spaces with the stack layout, where every window is part of the same stack.
+pub fn unstack_window() -> anyhow::Result<()> {
+    let windows = execute_yabai_cmd(&QueryWindows)
+        .context("Could not query windows")?
+        .context("Could not parse windows")?;
+
+    let active_window = windows
+        .iter()
+        .find(|window| window.has_focus)
+        .context("No window has focus")?;
+
+    if active_window.stack_index == 0 {
+        log::warn!("The active window {:?} is not stacked", active_window.id);
+        return Ok(());
+    }
+
+    let space = execute_yabai_cmd(&QuerySpaceByIndex::new(active_window.space_index))
+        .context("Could not query the space of the active window")?
+        .context("Could not parse the space of the active window")?;
+    anyhow::ensure!(
+        space.r#type != SpaceType::Stack,
+        "Space {:?} uses the stack layout, so all its windows are stacked. Change its layout to unstack windows",
+        space.index
+    );
+
+    // NOTE: yabai has no command to unstack a window. Floating the window removes it from the
+    // stack, and unfloating it inserts it back into the BSP tree.
+    log::info!("Unstacking window {:?}", active_window.id);
+    for _ in 0..2 {
+        execute_yabai_cmd(&ToggleWindow {
+            window_id: Some(active_window.id),
+            toggle: WindowToggle::Float,
+        })
+        .with_context(|| format!("Cannot toggle float for window {:?}", active_window.id))?;
+    }
+
+    Ok(())
+}
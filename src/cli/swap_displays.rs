@@ -0,0 +1,71 @@
+use itertools::Itertools;
+
+use crate::{config::FocusConfig, display_target::DisplayTarget, yabai::transport::Display};
+
+use super::{
+    focus_display::find_active_display, focus_space::focus_space_with_uuid,
+    move_space::swap_visible_spaces, rebalance::query_spaces_and_displays,
+};
+
+/// Exchanges the visible spaces of the active display and the target display, then focuses the
+/// originally focused space.
+///
+/// Without a target, the other display is used when there are exactly two displays.
+pub fn swap_displays(
+    target: Option<&DisplayTarget>,
+    focus_config: &FocusConfig,
+) -> anyhow::Result<()> {
+    let (spaces, displays) = query_spaces_and_displays()?;
+    let active_display = find_active_display(&spaces, &displays)?;
+
+    let target_display = match target {
+        Some(target) => target.resolve(&displays, active_display, focus_config)?,
+        None => find_other_display(&displays, active_display)?,
+    };
+
+    if target_display.index == active_display.index {
+        log::info!("The target display is the active display, nothing to swap");
+        return Ok(());
+    }
+
+    let swapped_spaces = swap_visible_spaces(&spaces, active_display, target_display)?;
+
+    focus_space_with_uuid(&swapped_spaces.target_space_uuid)?;
+    focus_space_with_uuid(&swapped_spaces.source_space_uuid)
+}
+
+fn find_other_display<'d>(
+    displays: &'d [Display],
+    active_display: &Display,
+) -> anyhow::Result<&'d Display> {
+    displays
+        .iter()
+        .filter(|display| display.index != active_display.index)
+        .exactly_one()
+        .map_err(|others| {
+            anyhow::anyhow!(
+                "Specify the display to swap with, there are {} other displays",
+                others.count()
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yabai::transport::{fixtures, Frame};
+
+    #[test]
+    fn finds_the_other_display_only_when_there_are_two() {
+        let displays = [
+            fixtures::display(1, Frame::default()),
+            fixtures::display(2, Frame::default()),
+        ];
+
+        assert_eq!(
+            2,
+            find_other_display(&displays, &displays[0]).unwrap().index.0
+        );
+        assert!(find_other_display(&displays[..1], &displays[0]).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::Context;
+
+use crate::{
+    config::FocusConfig,
+    position::{get_element_to_focus, Direction},
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::{FocusWindowById, MoveWindowToSpace, SwapWindow},
+    },
+};
+
+use super::move_window_in_direction::{introspect_windows, IntrospectedWindows};
+
+/// Swaps the active window with the closest window in a given direction.
+///
+/// Unlike moving the window, swapping keeps the structure of the BSP tree. Works across displays.
+///
+/// When the target window is in another space, only the active window takes its position. The
+/// target window is moved to the original space of the active window, where yabai inserts it
+/// like any moved window, so its previous position in the tree is not restored.
+pub fn swap_window_in_direction(
+    direction: Direction,
+    focus_config: &FocusConfig,
+) -> anyhow::Result<()> {
+    let IntrospectedWindows {
+        active_window,
+        other_visible_windows,
+    } = introspect_windows()?;
+
+    let Some(target_window) = get_element_to_focus(
+        &active_window.frame,
+        &other_visible_windows,
+        direction,
+        focus_config.scoring,
+    ) else {
+        log::warn!("No window in direction {direction:?}");
+        return Ok(());
+    };
+    log::trace!("The closest window in direction {direction:?} is {target_window:?}");
+
+    let swap_windows = || {
+        execute_yabai_cmd(&SwapWindow {
+            window_id: active_window.id,
+            other_window_id: target_window.id,
+        })
+        .with_context(|| {
+            format!(
+                "Cannot swap window {:?} with window {:?}",
+                active_window.id, target_window.id
+            )
+        })
+    };
+
+    if target_window.space_index == active_window.space_index {
+        log::info!(
+            "Swapping window {:?} with window {:?} in the same space",
+            active_window.id,
+            target_window.id
+        );
+        swap_windows()?;
+    } else {
+        log::info!(
+            "Window {:?} is in another space ({:?}). Moving the active window there before swapping",
+            target_window.id,
+            target_window.space_index
+        );
+
+        let move_window = |window_id, target_space_label: String| {
+            execute_yabai_cmd(&MoveWindowToSpace {
+                window_id: Some(window_id),
+                target_space_label: target_space_label.clone(),
+            })
+            .with_context(|| {
+                format!("Cannot move window {window_id:?} to space {target_space_label}")
+            })
+        };
+
+        move_window(active_window.id, target_window.space_index.to_string())?;
+        swap_windows()?;
+
+        // NOTE: yabai inserts the window at its insertion point, not at the original position of
+        // the active window
+        log::info!(
+            "Moving window {:?} to the original space of the active window ({:?})",
+            target_window.id,
+            active_window.space_index
+        );
+        move_window(target_window.id, active_window.space_index.to_string())?;
+    }
+
+    log::info!("Focusing the swapped window {:?}", active_window.id);
+    execute_yabai_cmd(&FocusWindowById::new(active_window.id))
+        .with_context(|| format!("Cannot focus window {:?}", active_window.id))
+}
@@ -0,0 +1,196 @@
+use anyhow::Context;
+use clap::ValueEnum;
+
+use crate::{
+    config::FloatConfig,
+    yabai::{
+        cli::execute_yabai_cmd,
+        command::{
+            MoveWindowAbsolute, QueryDisplays, QueryWindows, ResizeHandle, ResizeWindow,
+            ToggleWindow, WindowToggle,
+        },
+        transport::{Frame, Window},
+    },
+};
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowProperty {
+    /// When the window starts floating, it is centered on its display.
+    Float,
+    Sticky,
+    ZoomParent,
+    ZoomFullscreen,
+    NativeFullscreen,
+    /// Picture-in-picture.
+    Pip,
+}
+
+/// Desired state of a window property.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PropertyState {
+    On,
+    Off,
+}
+
+impl From<WindowProperty> for WindowToggle {
+    fn from(value: WindowProperty) -> Self {
+        match value {
+            WindowProperty::Float => WindowToggle::Float,
+            WindowProperty::Sticky => WindowToggle::Sticky,
+            WindowProperty::ZoomParent => WindowToggle::ZoomParent,
+            WindowProperty::ZoomFullscreen => WindowToggle::ZoomFullscreen,
+            WindowProperty::NativeFullscreen => WindowToggle::NativeFullscreen,
+            WindowProperty::Pip => WindowToggle::Pip,
+        }
+    }
+}
+
+impl WindowProperty {
+    /// Whether the property is enabled for the window. `None` when yabai does not report it.
+    fn is_enabled(self, window: &Window) -> Option<bool> {
+        match self {
+            WindowProperty::Float => Some(window.is_floating),
+            WindowProperty::Sticky => Some(window.is_sticky),
+            WindowProperty::ZoomParent => Some(window.has_parent_zoom),
+            WindowProperty::ZoomFullscreen => Some(window.has_fullscreen_zoom),
+            WindowProperty::NativeFullscreen => Some(window.is_native_fullscreen),
+            WindowProperty::Pip => None,
+        }
+    }
+}
+
+/// Toggles a property of the active window, or turns it on or off when `state` is given.
+///
+/// The property is only toggled when its current state differs from `state`.
+pub fn toggle_window_property(
+    property: WindowProperty,
+    state: Option<PropertyState>,
+    float_config: &FloatConfig,
+) -> anyhow::Result<()> {
+    let windows = execute_yabai_cmd(&QueryWindows)
+        .context("Could not query windows")?
+        .context("Could not parse windows")?;
+
+    let active_window = windows
+        .iter()
+        .find(|window| window.has_focus)
+        .context("No window has focus")?;
+
+    if matches!(
+        property,
+        WindowProperty::ZoomParent | WindowProperty::ZoomFullscreen
+    ) {
+        anyhow::ensure!(
+            !active_window.is_floating,
+            "Cannot zoom the floating window {:?}. Only tiled windows can be zoomed",
+            active_window.id
+        );
+    }
+
+    let was_enabled = property.is_enabled(active_window);
+    match (was_enabled, state) {
+        (Some(was_enabled), Some(state)) if was_enabled == (state == PropertyState::On) => {
+            log::info!(
+                "{property:?} is already {state:?} for window {:?}",
+                active_window.id
+            );
+            return Ok(());
+        }
+        (Some(was_enabled), _) => log::info!(
+            "Turning {property:?} {} for window {:?}",
+            if was_enabled { "off" } else { "on" },
+            active_window.id
+        ),
+        (None, Some(state)) => anyhow::bail!(
+            "Cannot turn {property:?} {state:?} for window {:?}, since yabai does not report its state. Toggle it instead",
+            active_window.id
+        ),
+        (None, None) => log::info!("Toggling {property:?} for window {:?}", active_window.id),
+    }
+
+    execute_yabai_cmd(&ToggleWindow {
+        window_id: Some(active_window.id),
+        toggle: property.into(),
+    })
+    .with_context(|| {
+        format!(
+            "Cannot toggle {property:?} for window {:?}",
+            active_window.id
+        )
+    })?;
+
+    if property == WindowProperty::Float && was_enabled == Some(false) {
+        center_floating_window(active_window, float_config.size_ratio)
+            .context("Cannot center the floating window")?;
+    }
+
+    Ok(())
+}
+
+fn center_floating_window(window: &Window, size_ratio: f32) -> anyhow::Result<()> {
+    let displays = execute_yabai_cmd(&QueryDisplays)
+        .context("Could not query displays")?
+        .context("Could not parse displays")?;
+
+    let display = displays
+        .iter()
+        .find(|display| display.index == window.display_index)
+        .with_context(|| format!("Could not find the display of window {:?}", window.id))?;
+
+    let frame = get_centered_frame(&display.frame, size_ratio);
+    log::debug!("Centering window {:?} at {frame:?}", window.id);
+
+    execute_yabai_cmd(&ResizeWindow {
+        handle: ResizeHandle::Absolute,
+        dx: frame.width.round() as i32,
+        dy: frame.height.round() as i32,
+    })
+    .with_context(|| format!("Cannot resize window {:?}", window.id))?;
+
+    execute_yabai_cmd(&MoveWindowAbsolute {
+        x: frame.x.round() as i32,
+        y: frame.y.round() as i32,
+    })
+    .with_context(|| format!("Cannot move window {:?}", window.id))
+}
+
+fn get_centered_frame(display_frame: &Frame, size_ratio: f32) -> Frame {
+    debug_assert!(
+        size_ratio > 0.0 && size_ratio <= 1.0,
+        "The size ratio is validated when loading the config"
+    );
+    let width = display_frame.width * size_ratio;
+    let height = display_frame.height * size_ratio;
+
+    Frame {
+        x: display_frame.x + (display_frame.width - width) / 2.0,
+        y: display_frame.y + (display_frame.height - height) / 2.0,
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn centers_frame_on_display() {
+        let display_frame = Frame {
+            x: -1000.0,
+            y: 0.0,
+            width: 1000.0,
+            height: 800.0,
+        };
+
+        assert_eq!(
+            Frame {
+                x: -750.0,
+                y: 200.0,
+                width: 500.0,
+                height: 400.0,
+            },
+            get_centered_frame(&display_frame, 0.5)
+        );
+    }
+}
@@ -0,0 +1,627 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    label::space::StableSpaceIndex,
+    position::{CardinalDirection, ScoringStrategy},
+    window_criteria::{deserialize_optional_regex, WindowCriteria},
+    yabai::transport::SpaceType,
+};
+
+/// Configuration read from a TOML file.
+///
+/// Every option is optional. A missing configuration file is the same as an empty one.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// Names of displays, mapped to their UUIDs.
+    ///
+    /// Names can be used instead of UUIDs in the config and in commands that take a display.
+    pub displays: BTreeMap<String, String>,
+
+    /// Per-space options, keyed by the stable index.
+    pub spaces: BTreeMap<StableSpaceIndex, SpaceConfig>,
+
+    /// Presets applied with `yabaictl profile apply` when a set of displays is connected.
+    pub profiles: BTreeMap<String, ProfileConfig>,
+
+    pub reorder: ReorderConfig,
+
+    pub focus: FocusConfig,
+
+    pub stack: StackConfig,
+
+    pub float: FloatConfig,
+
+    pub placeholders: PlaceholdersConfig,
+
+    pub destroy_spaces: DestroySpacesConfig,
+
+    /// Spaces that windows are assigned to, applied with `yabaictl apply-rules`.
+    /// The first matching rule wins.
+    #[serde(deserialize_with = "deserialize_window_rules")]
+    pub rules: Vec<WindowRule>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct SpaceConfig {
+    /// Name or UUID of the display the space should be placed on.
+    pub display: Option<String>,
+
+    /// Layout applied with `yabaictl profile apply`.
+    pub layout: Option<SpaceType>,
+
+    /// Description added to the label of spaces created with `yabaictl ensure-spaces`,
+    /// e.g. `Music` for `5: Music`.
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ProfileConfig {
+    /// Names or UUIDs of the displays. The profile is detected when exactly these displays are
+    /// connected.
+    pub displays: Vec<String>,
+
+    /// Per-space options that override the top-level `spaces` section.
+    pub spaces: BTreeMap<StableSpaceIndex, SpaceConfig>,
+
+    /// Padding around windows on every edge of the displays, in pixels.
+    pub padding: Option<u32>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ReorderConfig {
+    /// Send spaces between displays, so that lower stable indexes end up on displays that come
+    /// first in `display_order`.
+    ///
+    /// Each display keeps the same number of labeled spaces.
+    pub across_displays: bool,
+
+    pub display_order: DisplayOrder,
+
+    pub unlabeled: UnlabeledSpacesPolicy,
+}
+
+impl ReorderConfig {
+    /// Reorders spaces only within their displays. Used after commands that send spaces to
+    /// displays on purpose, so the reorder does not undo it.
+    pub fn within_displays(&self) -> Self {
+        Self {
+            across_displays: false,
+            ..self.clone()
+        }
+    }
+}
+
+/// Where to put spaces without a stable index when reordering spaces on a display.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum UnlabeledSpacesPolicy {
+    /// Before spaces with stable indexes.
+    First,
+    /// After spaces with stable indexes.
+    Last,
+    /// Do not move unlabeled spaces.
+    #[default]
+    Keep,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DisplayOrder {
+    /// Sort displays by the position of their left edge, then by their top edge.
+    #[default]
+    LeftToRight,
+
+    /// Sort displays by the position of their UUID in the list. Display names from the `displays`
+    /// section are resolved to UUIDs when the config is loaded.
+    /// Displays that are not listed come last, sorted left to right.
+    Uuids(Vec<String>),
+}
+
+/// Choosing windows, spaces and displays in a given direction.
+///
+/// Applies to focusing, moving, swapping and stacking windows, and moving spaces.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct FocusConfig {
+    pub scoring: ScoringStrategy,
+
+    /// When there is nothing in a given direction, wrap around to the farthest element in the
+    /// opposite direction, like i3's `focus_wrapping`.
+    ///
+    /// Applies to focusing and moving windows, and moving spaces.
+    pub wrap: bool,
+}
+
+/// Focusing windows in a given direction inside a stack.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct StackConfig {
+    /// Focuses the previous window in the stack, unless the first window is focused.
+    pub previous_direction: CardinalDirection,
+    /// Focuses the next window in the stack, unless the last window is focused.
+    pub next_direction: CardinalDirection,
+}
+
+impl Default for StackConfig {
+    fn default() -> Self {
+        Self {
+            previous_direction: CardinalDirection::North,
+            next_direction: CardinalDirection::South,
+        }
+    }
+}
+
+/// Windows that start floating with `yabaictl toggle float`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct FloatConfig {
+    /// Size of the floating window relative to its display, greater than 0 and at most 1.
+    /// The window is centered on the display.
+    #[serde(deserialize_with = "deserialize_size_ratio")]
+    pub size_ratio: f32,
+}
+
+impl Default for FloatConfig {
+    fn default() -> Self {
+        Self { size_ratio: 0.6 }
+    }
+}
+
+fn deserialize_size_ratio<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<f32, D::Error> {
+    let size_ratio = f32::deserialize(deserializer)?;
+    if size_ratio > 0.0 && size_ratio <= 1.0 {
+        Ok(size_ratio)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "size ratio must be greater than 0 and at most 1, got {size_ratio}"
+        )))
+    }
+}
+
+/// Empty spaces created so that the last space of a display can be sent to another display,
+/// e.g. by `yabaictl move-space --create-extra-space-if-last-on-display` or `yabaictl rebalance`.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PlaceholdersConfig {
+    pub cleanup: PlaceholderCleanup,
+}
+
+/// What happens to placeholder spaces after spaces are sent between displays.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlaceholderCleanup {
+    /// Leave placeholder spaces unlabeled.
+    #[default]
+    Keep,
+    /// Label placeholder spaces with the lowest free stable index.
+    Label,
+    /// Destroy empty placeholder spaces once another space occupies their display.
+    Destroy,
+}
+
+/// Empty background spaces that are kept by `yabaictl focus-space --destroy-empty-background-spaces`.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct DestroySpacesConfig {
+    /// Keep spaces with a description in their label, e.g. `5: Music`.
+    pub keep_described: bool,
+
+    /// Stable indexes of spaces that are never destroyed.
+    pub keep_indexes: BTreeSet<StableSpaceIndex>,
+
+    /// Number of spaces that are kept on every display.
+    pub min_spaces_per_display: usize,
+
+    /// Only destroy spaces that have not been visible for this many seconds.
+    pub unfocused_timeout: Option<u64>,
+
+    /// Consider spaces with only minimized or hidden windows empty.
+    pub ignore_hidden_windows: bool,
+}
+
+/// Assigns matching windows to a space, like i3's `assign [class="..."] <workspace>`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct WindowRule {
+    /// Application name, compared case-insensitively.
+    #[serde(default)]
+    pub app: Option<String>,
+
+    #[serde(default, deserialize_with = "deserialize_optional_regex")]
+    pub title_regex: Option<Regex>,
+
+    /// Stable index of the space the window is moved to.
+    pub space: StableSpaceIndex,
+
+    /// Whether the window should float. Unchanged when not set.
+    #[serde(default)]
+    pub float: Option<bool>,
+
+    /// Whether the window should be visible on all spaces. Unchanged when not set.
+    #[serde(default)]
+    pub sticky: Option<bool>,
+}
+
+/// Rejects rules without criteria, since they would match every window.
+fn deserialize_window_rules<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<WindowRule>, D::Error> {
+    let rules = Vec::<WindowRule>::deserialize(deserializer)?;
+
+    match rules
+        .iter()
+        .position(|rule| rule.app.is_none() && rule.title_regex.is_none())
+    {
+        Some(position) => Err(serde::de::Error::custom(format!(
+            "rule {} must have an app or a title-regex",
+            position + 1
+        ))),
+        None => Ok(rules),
+    }
+}
+
+impl WindowRule {
+    pub fn criteria(&self) -> WindowCriteria {
+        WindowCriteria {
+            app: self.app.clone(),
+            title_regex: self.title_regex.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum LoadConfigError {
+    #[error("Cannot read config file {path:?}")]
+    Read {
+        path: PathBuf,
+
+        #[source]
+        cause: io::Error,
+    },
+
+    #[error("Cannot parse config file {path:?}")]
+    Parse {
+        path: PathBuf,
+
+        #[source]
+        cause: toml::de::Error,
+    },
+}
+
+impl Config {
+    /// Reads the config from `path`.
+    ///
+    /// When no path is given, the default location is used and a missing file results in the
+    /// default config.
+    pub fn load(path: Option<&Path>) -> Result<Self, LoadConfigError> {
+        let (path, must_exist) = match path {
+            Some(path) => (path.to_owned(), true),
+            None => match default_config_path() {
+                Some(path) => (path, false),
+                None => {
+                    log::debug!(
+                        "Cannot determine the default config path. Using the default config"
+                    );
+                    return Ok(Self::default());
+                }
+            },
+        };
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound && !must_exist => {
+                log::debug!("Config file {path:?} does not exist. Using the default config");
+                return Ok(Self::default());
+            }
+            Err(cause) => return Err(LoadConfigError::Read { path, cause }),
+        };
+
+        let config: Self =
+            toml::from_str(&contents).map_err(|cause| LoadConfigError::Parse { path, cause })?;
+
+        Ok(config.with_resolved_display_names())
+    }
+
+    /// Replaces display names in `reorder.display-order` with their UUIDs, so they can be compared
+    /// with the UUIDs of connected displays.
+    fn with_resolved_display_names(mut self) -> Self {
+        if let DisplayOrder::Uuids(names_or_uuids) = &self.reorder.display_order {
+            let uuids = names_or_uuids
+                .iter()
+                .map(|name_or_uuid| self.display_uuid(name_or_uuid).to_owned())
+                .collect();
+            self.reorder.display_order = DisplayOrder::Uuids(uuids);
+        }
+
+        self
+    }
+
+    /// UUID of the display the space with a given stable index is assigned to.
+    pub fn assigned_display(&self, stable_index: StableSpaceIndex) -> Option<&str> {
+        self.spaces
+            .get(&stable_index)
+            .and_then(|space_config| space_config.display.as_deref())
+            .map(|display| self.display_uuid(display))
+    }
+
+    /// Description of the space with a given stable index.
+    pub fn space_description(&self, stable_index: StableSpaceIndex) -> Option<&str> {
+        self.spaces
+            .get(&stable_index)
+            .and_then(|space_config| space_config.description.as_deref())
+    }
+
+    /// UUID of the display with a given name. Anything else is assumed to be a UUID already.
+    pub fn display_uuid<'a>(&'a self, name_or_uuid: &'a str) -> &'a str {
+        self.displays
+            .get(name_or_uuid)
+            .map_or(name_or_uuid, String::as_str)
+    }
+
+    /// Name of the profile whose displays are exactly the connected ones.
+    pub fn detect_profile<'a>(
+        &self,
+        connected_display_uuids: impl IntoIterator<Item = &'a str>,
+    ) -> Option<&str> {
+        let connected_display_uuids: BTreeSet<_> = connected_display_uuids
+            .into_iter()
+            .map(str::to_ascii_uppercase)
+            .collect();
+
+        self.profiles
+            .iter()
+            .find(|(_, profile)| {
+                let profile_display_uuids: BTreeSet<_> = profile
+                    .displays
+                    .iter()
+                    .map(|display| self.display_uuid(display).to_ascii_uppercase())
+                    .collect();
+
+                profile_display_uuids == connected_display_uuids
+            })
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// The config with the per-space options of a profile applied on top.
+    pub fn with_profile(&self, profile_name: &str) -> Option<Self> {
+        let profile = self.profiles.get(profile_name)?;
+
+        let mut config = self.clone();
+        for (stable_index, space_config) in &profile.spaces {
+            let merged_space_config = config.spaces.entry(*stable_index).or_default();
+            if space_config.display.is_some() {
+                merged_space_config
+                    .display
+                    .clone_from(&space_config.display);
+            }
+            if space_config.layout.is_some() {
+                merged_space_config.layout = space_config.layout;
+            }
+            if space_config.description.is_some() {
+                merged_space_config
+                    .description
+                    .clone_from(&space_config.description);
+            }
+        }
+
+        Some(config)
+    }
+}
+
+/// `$XDG_CONFIG_HOME/yabaictl/config.toml`, falling back to `~/.config/yabaictl/config.toml`.
+fn default_config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_home.join("yabaictl").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_space_display_assignments() {
+        let config: Config = toml::from_str(
+            r#"
+            [spaces.1]
+            display = "37D8832A-2D66-02CA-B9F7-8F30A301B230"
+
+            [spaces.10]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Some("37D8832A-2D66-02CA-B9F7-8F30A301B230"),
+            config.assigned_display("1".parse().unwrap())
+        );
+        assert_eq!(None, config.assigned_display("10".parse().unwrap()));
+        assert_eq!(None, config.assigned_display("2".parse().unwrap()));
+    }
+
+    #[test]
+    fn parses_display_order() {
+        let config: Config = toml::from_str(
+            r#"
+            [reorder]
+            across-displays = true
+            display-order = { uuids = ["first", "second"] }
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.reorder.across_displays);
+        assert_eq!(
+            DisplayOrder::Uuids(vec!["first".to_owned(), "second".to_owned()]),
+            config.reorder.display_order
+        );
+
+        let config: Config = toml::from_str("reorder.display-order = \"left-to-right\"").unwrap();
+        assert_eq!(DisplayOrder::LeftToRight, config.reorder.display_order);
+        assert_eq!(UnlabeledSpacesPolicy::Keep, config.reorder.unlabeled);
+    }
+
+    #[test]
+    fn parses_window_rules() {
+        let config: Config = toml::from_str(
+            r#"
+            [[rules]]
+            app = "Slack"
+            space = 5
+
+            [[rules]]
+            title-regex = "^Picture-in-Picture$"
+            space = 1
+            float = true
+            sticky = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(2, config.rules.len());
+        assert_eq!(Some("Slack"), config.rules[0].app.as_deref());
+        assert_eq!(None, config.rules[0].float);
+        assert_eq!(Some(true), config.rules[1].float);
+        assert!(config.rules[1]
+            .title_regex
+            .as_ref()
+            .is_some_and(|title_regex| title_regex.is_match("Picture-in-Picture")));
+
+        assert!(toml::from_str::<Config>("[[rules]]\ntitle-regex = \"(\"\nspace = 1").is_err());
+        assert!(toml::from_str::<Config>("[[rules]]\nspace = 1\nfloat = true").is_err());
+    }
+
+    #[test]
+    fn accepts_only_cardinal_stack_directions() {
+        let config: Config = toml::from_str("stack.next-direction = \"east\"").unwrap();
+        assert_eq!(CardinalDirection::East, config.stack.next_direction);
+
+        assert!(toml::from_str::<Config>("stack.next-direction = \"north-east\"").is_err());
+        assert!(toml::from_str::<Config>("stack.previous-direction = \"closest\"").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_float_size_ratios() {
+        let size_ratio =
+            |toml| toml::from_str::<Config>(toml).map(|config| config.float.size_ratio);
+
+        assert_eq!(Ok(0.5), size_ratio("float.size-ratio = 0.5").map_err(drop));
+        assert!(size_ratio("float.size-ratio = 0").is_err());
+        assert!(size_ratio("float.size-ratio = -0.5").is_err());
+        assert!(size_ratio("float.size-ratio = 1.5").is_err());
+    }
+
+    #[test]
+    fn resolves_display_names() {
+        let config: Config = toml::from_str(
+            r#"
+            [displays]
+            laptop = "37D8832A-2D66-02CA-B9F7-8F30A301B230"
+
+            [spaces.1]
+            display = "laptop"
+
+            [spaces.2]
+            display = "F466F621-B5FA-04A0-0800-CFA6C258DECD"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Some("37D8832A-2D66-02CA-B9F7-8F30A301B230"),
+            config.assigned_display("1".parse().unwrap())
+        );
+        assert_eq!(
+            Some("F466F621-B5FA-04A0-0800-CFA6C258DECD"),
+            config.assigned_display("2".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn resolves_display_names_in_display_order() {
+        let config: Config = toml::from_str(
+            r#"
+            displays.laptop = "LAPTOP-UUID"
+            reorder.display-order = { uuids = ["laptop", "OTHER-UUID"] }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            DisplayOrder::Uuids(vec!["LAPTOP-UUID".to_owned(), "OTHER-UUID".to_owned()]),
+            config.with_resolved_display_names().reorder.display_order
+        );
+    }
+
+    #[test]
+    fn detects_and_applies_profiles() {
+        let config: Config = toml::from_str(
+            r#"
+            [displays]
+            laptop = "LAPTOP-UUID"
+            left-monitor = "LEFT-UUID"
+
+            [spaces.1]
+            display = "laptop"
+            layout = "stack"
+
+            [spaces.2]
+            display = "laptop"
+
+            [profiles.laptop-only]
+            displays = ["laptop"]
+
+            [profiles.office]
+            displays = ["laptop", "left-monitor"]
+            padding = 10
+
+            [profiles.office.spaces.2]
+            display = "left-monitor"
+            layout = "bsp"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(Some("laptop-only"), config.detect_profile(["laptop-uuid"]));
+        assert_eq!(
+            Some("office"),
+            config.detect_profile(["LEFT-UUID", "LAPTOP-UUID"])
+        );
+        assert_eq!(None, config.detect_profile(["LEFT-UUID"]));
+
+        let office = config.with_profile("office").unwrap();
+        let space_1 = &office.spaces[&"1".parse().unwrap()];
+        let space_2 = &office.spaces[&"2".parse().unwrap()];
+        assert_eq!(Some("laptop"), space_1.display.as_deref());
+        assert_eq!(Some(SpaceType::Stack), space_1.layout);
+        assert_eq!(Some("left-monitor"), space_2.display.as_deref());
+        assert_eq!(Some(SpaceType::BSP), space_2.layout);
+        assert_eq!(
+            Some("LEFT-UUID"),
+            office.assigned_display("2".parse().unwrap())
+        );
+
+        assert!(config.with_profile("home").is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_stable_indexes() {
+        assert!(toml::from_str::<Config>("[spaces.11]").is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use std::str::FromStr;
+
+use anyhow::Context;
+use thiserror::Error;
+
+use crate::{
+    config::{Config, FocusConfig},
+    position::{get_element_to_focus, get_element_to_wrap_to, Direction},
+    yabai::transport::Display,
+};
+
+/// A display specified on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisplayTarget {
+    /// The display in a given direction from the active display.
+    Direction(Direction),
+    /// Mission Control index of the display, starting at 1.
+    Index(u32),
+    /// UUID, yabai label or configured name of the display.
+    Name(String),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ParseDisplayTargetError {
+    #[error("The display cannot be empty")]
+    Empty,
+}
+
+impl FromStr for DisplayTarget {
+    type Err = ParseDisplayTargetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if s.is_empty() {
+            Err(ParseDisplayTargetError::Empty)
+        } else if let Ok(direction) = s.parse() {
+            Ok(Self::Direction(direction))
+        } else if let Ok(index) = s.parse() {
+            Ok(Self::Index(index))
+        } else {
+            Ok(Self::Name(s.to_owned()))
+        }
+    }
+}
+
+impl std::fmt::Display for DisplayTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisplayTarget::Direction(direction) => write!(f, "in direction {direction}"),
+            DisplayTarget::Index(index) => write!(f, "with index {index}"),
+            DisplayTarget::Name(name) => write!(f, "\"{name}\""),
+        }
+    }
+}
+
+impl DisplayTarget {
+    /// Replaces display names from the config with the UUIDs of the displays.
+    pub fn with_display_names(self, config: &Config) -> Self {
+        match self {
+            DisplayTarget::Name(name) => DisplayTarget::Name(config.display_uuid(&name).to_owned()),
+            target => target,
+        }
+    }
+
+    /// Finds the target display. Directions are relative to `active_display`.
+    pub fn resolve<'d>(
+        &self,
+        displays: &'d [Display],
+        active_display: &Display,
+        focus_config: &FocusConfig,
+    ) -> anyhow::Result<&'d Display> {
+        let display = match self {
+            DisplayTarget::Direction(direction) => get_element_to_focus(
+                &active_display.frame,
+                displays,
+                *direction,
+                focus_config.scoring,
+            )
+            .or_else(|| {
+                focus_config
+                    .wrap
+                    .then(|| get_element_to_wrap_to(&active_display.frame, displays, *direction))
+                    .flatten()
+            }),
+            DisplayTarget::Index(index) => {
+                displays.iter().find(|display| display.index.0 == *index)
+            }
+            DisplayTarget::Name(name) => displays.iter().find(|display| {
+                display.uuid.eq_ignore_ascii_case(name) || display.label.as_deref() == Some(name)
+            }),
+        };
+
+        display.with_context(|| format!("Cannot find the display {self}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yabai::transport::{fixtures, Frame};
+
+    fn display(index: u32, x: f32, label: Option<&str>) -> Display {
+        let frame = Frame {
+            x,
+            y: 0.0,
+            width: 100.0,
+            height: 100.0,
+        };
+
+        Display {
+            label: label.map(ToOwned::to_owned),
+            ..fixtures::display(index, frame)
+        }
+    }
+
+    #[test]
+    fn parses_display_targets() {
+        assert_eq!(
+            Ok(DisplayTarget::Direction(Direction::West)),
+            "west".parse()
+        );
+        assert_eq!(Ok(DisplayTarget::Index(2)), "2".parse());
+        assert_eq!(
+            Ok(DisplayTarget::Name("37D8832A-2D66".to_owned())),
+            "37D8832A-2D66".parse()
+        );
+        assert_eq!(
+            Err(ParseDisplayTargetError::Empty),
+            " ".parse::<DisplayTarget>()
+        );
+    }
+
+    #[test]
+    fn resolves_display_targets() {
+        let displays = [
+            display(1, 0.0, Some("main")),
+            display(2, 100.0, None),
+            display(3, 200.0, None),
+        ];
+        let resolve = |target: &str| {
+            target
+                .parse::<DisplayTarget>()
+                .unwrap()
+                .resolve(&displays, &displays[0], &FocusConfig::default())
+                .ok()
+                .map(|display| display.index.0)
+        };
+
+        assert_eq!(Some(2), resolve("east"));
+        assert_eq!(None, resolve("west"));
+        assert_eq!(Some(3), resolve("3"));
+        assert_eq!(None, resolve("4"));
+        assert_eq!(Some(1), resolve("main"));
+        assert_eq!(Some(2), resolve("DISPLAY-2"));
+        assert_eq!(None, resolve("unknown"));
+    }
+
+    #[test]
+    fn replaces_configured_display_names() {
+        let config: Config = toml::from_str("displays.laptop = \"display-1\"").unwrap();
+
+        assert_eq!(
+            DisplayTarget::Name("display-1".to_owned()),
+            DisplayTarget::Name("laptop".to_owned()).with_display_names(&config)
+        );
+        assert_eq!(
+            DisplayTarget::Index(2),
+            DisplayTarget::Index(2).with_display_names(&config)
+        );
+    }
+}
@@ -2,9 +2,17 @@ use std::{num::ParseIntError, ops::Deref, str::FromStr};
 
 use anyhow::Context;
 use log::debug;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 
-use crate::yabai::{self, cli::execute_yabai_cmd, transport::Space};
+use crate::{
+    state::State,
+    yabai::{
+        self,
+        cli::execute_yabai_cmd,
+        transport::{DisplayIndex, Space, SpaceIndex},
+    },
+};
 
 use super::Labelable;
 
@@ -63,6 +71,51 @@ impl FromStr for StableSpaceIndex {
     }
 }
 
+impl Serialize for StableSpaceIndex {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.0)
+    }
+}
+
+/// Accepts both integers and strings, since TOML table keys are always strings.
+impl<'de> Deserialize<'de> for StableSpaceIndex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StableSpaceIndexVisitor;
+
+        impl<'de> de::Visitor<'de> for StableSpaceIndexVisitor {
+            type Value = StableSpaceIndex;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(
+                    formatter,
+                    "a stable space index within the range [{}, {}]",
+                    SUPPORTED_STABLE_INDEXES.start(),
+                    SUPPORTED_STABLE_INDEXES.end()
+                )
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                u32::try_from(value)
+                    .map_err(|_| ParseStableSpaceIndexError::OutOfBounds)
+                    .and_then(StableSpaceIndex::try_from)
+                    .map_err(E::custom)
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                u64::try_from(value)
+                    .map_err(|_| E::custom(ParseStableSpaceIndexError::OutOfBounds))
+                    .and_then(|value| self.visit_u64(value))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(StableSpaceIndexVisitor)
+    }
+}
+
 #[derive(Debug, Error, PartialEq, Eq)]
 pub enum ParseSpaceLabelError {
     #[error("Colon is missing in the space label")]
@@ -114,18 +167,34 @@ impl Space {
 
         output
     }
+
+    /// The part of the label after the stable index, e.g. `Music` in `5: Music`.
+    pub fn label_description(&self) -> Option<&str> {
+        let (_, description) = self.label.as_deref()?.split_once(':')?;
+        let description = description.trim();
+
+        (!description.is_empty()).then_some(description)
+    }
 }
 
-pub fn create_space_with_label(label: String) -> anyhow::Result<Space> {
-    execute_yabai_cmd(&yabai::command::CreateSpace).context("Cannot create a new space")?;
+/// Creates a space with the label on `display_index`, or on the active display when it is `None`.
+pub fn create_space_with_label(
+    label: String,
+    display_index: Option<DisplayIndex>,
+) -> anyhow::Result<Space> {
+    execute_yabai_cmd(&yabai::command::CreateSpace { display_index })
+        .context("Cannot create a new space")?;
 
     let spaces = execute_yabai_cmd(&yabai::command::QuerySpaces {
-        only_current_display: true,
+        only_current_display: display_index.is_none(),
     })
     .context("Cannot query spaces")?
     .context("Cannot parse spaces")?;
 
-    let created_space = spaces.into_iter().last().expect("The created space is added as the last one in the current display. It must have at least 1 space");
+    let created_space = spaces
+        .into_iter()
+        .rfind(|space| display_index.is_none_or(|index| space.display_index == index))
+        .expect("The created space is added as the last one in its display. It must have at least 1 space");
 
     debug!(
         "Created new space with index {:?} on display {:?}",
@@ -143,9 +212,35 @@ pub fn create_space_with_label(label: String) -> anyhow::Result<Space> {
         )
     })?;
 
+    if let Ok(stable_index) = Space::parse_index(&label) {
+        apply_remembered_layout(created_space.index, stable_index);
+    }
+
     Ok(created_space)
 }
 
+/// Applies the layout last chosen for the stable index. Called whenever a space gets a stable
+/// index.
+///
+/// Failures are only logged, since the space is usable with the default layout.
+pub(crate) fn apply_remembered_layout(index: SpaceIndex, stable_index: StableSpaceIndex) {
+    let layout = match State::load() {
+        Ok(state) => state.space_layouts.get(&stable_index).copied(),
+        Err(error) => {
+            log::warn!("Cannot load the state to reapply the space layout: {error:?}");
+            None
+        }
+    };
+
+    if let Some(layout) = layout {
+        debug!("Reapplying layout {layout} to the space with stable index {stable_index:?}");
+
+        if let Err(error) = execute_yabai_cmd(&yabai::command::SetSpaceLayout { index, layout }) {
+            log::warn!("Cannot reapply layout {layout} to space {index:?}: {error:?}");
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,6 +251,20 @@ mod tests {
         assert_eq!("1:", Space::label(StableSpaceIndex(1), None));
     }
 
+    #[test]
+    fn gets_description_from_label() {
+        let description = |label| {
+            crate::yabai::transport::fixtures::space(1, label)
+                .label_description()
+                .map(ToOwned::to_owned)
+        };
+
+        assert_eq!(Some("Music".to_owned()), description(Some("5: Music")));
+        assert_eq!(None, description(Some("5:")));
+        assert_eq!(None, description(Some("5: ")));
+        assert_eq!(None, description(None));
+    }
+
     #[test]
     fn gets_space_index_from_label() {
         assert_eq!(Ok(StableSpaceIndex(10)), Space::parse_index("10: hello"));
@@ -1,5 +1,9 @@
 pub mod cli;
+pub mod config;
+pub mod display_target;
 pub mod label;
 pub mod position;
 pub mod simple_bar;
+pub mod state;
+pub mod window_criteria;
 pub mod yabai;
@@ -1,30 +1,58 @@
+use std::{path::PathBuf, sync::OnceLock, time::Duration};
+
 use anyhow::Context;
-use clap::{Args, Parser, Subcommand};
+use clap::{builder::PossibleValue, Args, Parser, Subcommand, ValueEnum};
+use regex::Regex;
 use yabaictl::{
     cli::{
+        apply_rules::apply_window_rules,
         destroy_spaces,
+        ensure_spaces::ensure_spaces,
+        focus_display::focus_display,
         focus_space::{focus_next_or_previous_space, focus_space_by_label, NextOrPrevious},
+        focus_window_by_criteria::focus_window_by_criteria,
         focus_window_in_direction::focus_window_in_direction,
+        focus_window_in_order::focus_next_or_previous_window,
         label_spaces::label_spaces,
-        move_space_in_direction::move_space_in_direction,
+        move_space::{move_space_to_display, MoveSpaceOptions},
         move_window_in_direction::move_window_in_direction,
+        move_window_to_display::move_window_to_display,
         move_window_to_space::move_window_to_space,
+        placeholder_spaces::clean_up_placeholder_spaces,
+        profile::{apply_profile, print_detected_profile, ProfileCommand},
+        raise_or_run::raise_or_run,
+        rebalance::rebalance_spaces,
         reorder::reorder_spaces_by_stable_indexes,
+        resize_window::{resize_window_in_direction, ResizeAmount},
         set_space_label::{set_space_label, SetSpaceLabelArgs},
+        set_space_layout::{set_space_layout, LayoutCommand},
+        stack_window::{stack_window_in_direction, unstack_window},
+        swap_displays::swap_displays,
+        swap_window::swap_window_in_direction,
+        toggle_window::{toggle_window_property, PropertyState, WindowProperty},
     },
+    config::{Config, FocusConfig, UnlabeledSpacesPolicy},
+    display_target::DisplayTarget,
     label::space::StableSpaceIndex,
-    position::Direction,
+    position::{CardinalDirection, Direction},
     simple_bar,
+    window_criteria::WindowCriteria,
     yabai::transport::Space,
 };
 
-// TODO: add a config file with:
-// * options with defaults for `create_if_not_found` and `destroy_empty_background_spaces`
+// TODO: add config options with:
+// * defaults for `create_if_not_found` and `destroy_empty_background_spaces`
 // * default space descriptions to use when using stable-indexed spaces
 
 #[derive(Parser)]
 #[command(author, about, version)]
 struct Cli {
+    /// Path to the config file.
+    ///
+    /// Defaults to `$XDG_CONFIG_HOME/yabaictl/config.toml` or `~/.config/yabaictl/config.toml`.
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Command,
 }
@@ -44,6 +72,56 @@ struct FocusSpaceSpecifier {
     stable_index: Option<StableSpaceIndex>,
 }
 
+#[derive(Args, Clone)]
+#[group(required = true, multiple = true)]
+struct FocusWindowSpecifier {
+    /// Direction based on the active window, or the next or previous window across all spaces.
+    #[arg(conflicts_with_all = ["app", "title_regex"])]
+    target: Option<FocusWindowTarget>,
+
+    /// Name of the application that owns the window. Case-insensitive.
+    #[arg(long)]
+    app: Option<String>,
+
+    /// Regular expression that the window title must match.
+    #[arg(long)]
+    title_regex: Option<Regex>,
+}
+
+#[derive(Clone, Copy)]
+enum FocusWindowTarget {
+    Direction(Direction),
+    /// Windows are ordered by display, stable index of their space and position.
+    NextOrPrevious(NextOrPrevious),
+}
+
+impl ValueEnum for FocusWindowTarget {
+    fn value_variants<'a>() -> &'a [Self] {
+        static VARIANTS: OnceLock<Vec<FocusWindowTarget>> = OnceLock::new();
+
+        VARIANTS.get_or_init(|| {
+            Direction::value_variants()
+                .iter()
+                .copied()
+                .map(FocusWindowTarget::Direction)
+                .chain(
+                    NextOrPrevious::value_variants()
+                        .iter()
+                        .copied()
+                        .map(FocusWindowTarget::NextOrPrevious),
+                )
+                .collect()
+        })
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            Self::Direction(direction) => direction.to_possible_value(),
+            Self::NextOrPrevious(next_or_previous) => next_or_previous.to_possible_value(),
+        }
+    }
+}
+
 #[derive(Args)]
 struct TargetSpaceUsingStableIndexOptions {
     /// If the target space does not exist, it will be created before focusing it.
@@ -52,6 +130,37 @@ struct TargetSpaceUsingStableIndexOptions {
     create_if_not_found: bool,
 }
 
+#[derive(Args)]
+struct WrapOptions {
+    /// When there is nothing in the direction, wrap around to the farthest window or display in
+    /// the opposite direction.
+    ///
+    /// Defaults to the `focus.wrap` config option.
+    #[arg(long, default_value_t = false, conflicts_with = "no_wrap")]
+    wrap: bool,
+
+    /// Do not wrap around, even when the `focus.wrap` config option is enabled.
+    #[arg(long, default_value_t = false)]
+    no_wrap: bool,
+}
+
+impl WrapOptions {
+    fn apply_to(&self, focus_config: &FocusConfig) -> FocusConfig {
+        let wrap = if self.wrap {
+            true
+        } else if self.no_wrap {
+            false
+        } else {
+            focus_config.wrap
+        };
+
+        FocusConfig {
+            wrap,
+            ..focus_config.clone()
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum MoveWindowSpaceSpecifier {
     /// Move the window to a desired space, specified by a stable index.
@@ -63,7 +172,20 @@ enum MoveWindowSpaceSpecifier {
     },
     /// Move the window in a given direction.
     /// Supports moving the window across displays.
-    InDirection { direction: Direction },
+    InDirection {
+        direction: Direction,
+
+        #[command(flatten)]
+        wrap_options: WrapOptions,
+    },
+    /// Move the window to the visible space of a display. The window stays focused.
+    ToDisplay {
+        /// Direction, index, UUID or label of the display.
+        target: DisplayTarget,
+
+        #[command(flatten)]
+        wrap_options: WrapOptions,
+    },
 }
 
 #[derive(Subcommand)]
@@ -82,27 +204,91 @@ enum Command {
         /// This helps manage the spaces, since unused spaces won't take up slots on displays.
         ///
         /// Useful with the `create_if_not_found` option.
+        ///
+        /// Spaces to keep can be configured in the `destroy-spaces` config section.
         #[arg(long, default_value_t = false)]
         destroy_empty_background_spaces: bool,
+
+        /// Skip native fullscreen spaces when focusing the next or previous space.
+        #[arg(long, default_value_t = false)]
+        skip_native_fullscreen: bool,
     },
-    /// Focuses a window in a given direction based on the active window.
+    /// Focuses a window in a given direction based on the active window, the next or previous
+    /// window, or a window matching the given app and title.
     /// Works across displays.
-    FocusWindow { direction: Direction },
-    /// Move the active space in a given direction across displays.
+    ///
+    /// In a stack, north and south (see the `stack` config section) cycle through the stacked
+    /// windows before leaving the stack.
+    ///
+    /// Repeatedly focusing windows by app or title cycles through all matching windows.
+    FocusWindow {
+        #[command(flatten)]
+        window_specifier: FocusWindowSpecifier,
+
+        #[command(flatten)]
+        wrap_options: WrapOptions,
+    },
+    /// Focuses the visible space of a display.
+    FocusDisplay {
+        /// Direction, index, UUID or label of the display.
+        target: DisplayTarget,
+
+        #[command(flatten)]
+        wrap_options: WrapOptions,
+    },
+    /// Move the active space to another display.
+    ///
+    /// With `--swap`, the visible space of the target display is sent back to the source
+    /// display, like i3's `move workspace to output`.
     MoveSpace {
-        direction: Direction,
+        /// Direction, index, UUID or label of the display.
+        target: DisplayTarget,
 
-        /// Yabai does not allow moving the last space from a display.
+        #[command(flatten)]
+        options: MoveSpaceOptions,
+
+        #[command(flatten)]
+        wrap_options: WrapOptions,
+    },
+    /// Exchanges the visible spaces of the active display and another display, keeping the focus
+    /// on the originally focused space.
+    SwapDisplays {
+        /// Direction, index, UUID or label of the display. Optional with exactly two displays.
+        target: Option<DisplayTarget>,
+
+        #[command(flatten)]
+        wrap_options: WrapOptions,
+    },
+    /// Creates the missing spaces with stable indexes, on their assigned displays and with their
+    /// configured descriptions, then reorders spaces.
+    EnsureSpaces {
+        /// Ensure spaces with stable indexes from 1 to `count`.
         ///
-        /// This flag will make it so that in this situation, an extra space will be created for
-        /// that display in the background, so moving the active space is possible.
-        #[arg(long, default_value_t = false)]
-        create_extra_space_if_last_on_display: bool,
+        /// Defaults to the stable indexes in the `spaces` config section.
+        #[arg(long)]
+        count: Option<StableSpaceIndex>,
     },
     /// Assigns stable indexes to spaces using labels.
     LabelSpaces,
     /// Reorders spaces using their stable indexes, parsed from their labels.
-    ReorderByStableIndexes,
+    ReorderByStableIndexes {
+        /// Also send spaces between displays, so lower stable indexes are on the first displays.
+        ///
+        /// Defaults to the `reorder.across-displays` config option.
+        #[arg(long, default_value_t = false, conflicts_with = "no_across_displays")]
+        across_displays: bool,
+
+        /// Only reorder spaces within their displays, even when the `reorder.across-displays`
+        /// config option is enabled.
+        #[arg(long, default_value_t = false)]
+        no_across_displays: bool,
+
+        /// Where to put spaces without a stable index.
+        ///
+        /// Defaults to the `reorder.unlabeled` config option.
+        #[arg(long)]
+        unlabeled: Option<UnlabeledSpacesPolicy>,
+    },
     /// Assigns a label to a space.
     SetLabel(SetSpaceLabelArgs),
     /// Move the currently active window to another space or in a given direction.
@@ -110,59 +296,294 @@ enum Command {
         #[command(subcommand)]
         space_specifier: MoveWindowSpaceSpecifier,
     },
+    /// Sends spaces to the displays assigned to them in the config and reorders them.
+    ///
+    /// Useful after connecting a display, when macOS places spaces on arbitrary displays.
+    Rebalance,
+    /// Grows the active window in a given direction.
+    ///
+    /// When the window is at the display edge in that direction, it shrinks from the opposite
+    /// edge instead.
+    Resize {
+        direction: CardinalDirection,
+
+        /// Number of pixels (e.g. `20` or `20px`) or percentage of the display size (e.g. `5%`).
+        #[arg(long, default_value = "5%")]
+        amount: ResizeAmount,
+    },
+    /// Swaps the active window with the closest window in a given direction.
+    /// Works across displays. Focus stays on the active window.
+    ///
+    /// Across spaces, the other window is moved to the space of the active window without
+    /// restoring its position there.
+    SwapWindow { direction: Direction },
+    /// Changes the layout of a space.
+    Layout {
+        /// The space to change the layout of. Defaults to the focused space.
+        #[arg(long)]
+        stable_index: Option<StableSpaceIndex>,
+
+        #[command(subcommand)]
+        layout: LayoutCommand,
+    },
+    /// Stacks the active window onto the closest window in a given direction.
+    /// Works across displays.
+    Stack { direction: Direction },
+    /// Takes the active window out of its stack. Not available in spaces with the stack layout.
+    Unstack,
+    /// Toggles a property of the active window.
+    Toggle {
+        property: WindowProperty,
+
+        /// Turn the property on or off instead of toggling it. Nothing changes when the window
+        /// already is in that state.
+        state: Option<PropertyState>,
+    },
+    /// Manages presets for sets of connected displays, from the `profiles` config section.
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommand,
+    },
+    /// Moves windows to the spaces assigned by the `rules` config section.
+    ///
+    /// Useful after logging in, when apps open on arbitrary spaces.
+    ApplyRules,
+    /// Focuses a window of an app, or launches the app when it has no windows.
+    RaiseOrRun {
+        /// Name of the application that owns the window. Case-insensitive.
+        #[arg(long)]
+        app: String,
+
+        /// Regular expression that the window title must match.
+        #[arg(long)]
+        title_regex: Option<Regex>,
+
+        /// Shell command that launches the app, e.g. `open -a Slack`.
+        #[arg(long)]
+        cmd: String,
+
+        /// Number of seconds to wait for the launched app's window to appear, so it can be
+        /// focused. Does not wait by default.
+        #[arg(long, value_name = "SECONDS")]
+        wait_timeout: Option<u64>,
+
+        /// Moves the launched app's window to the space with this stable index.
+        #[arg(long, requires = "wait_timeout")]
+        stable_index: Option<StableSpaceIndex>,
+    },
+}
+
+impl Command {
+    /// Whether the command is pointless without the config, e.g. it applies the `spaces` or
+    /// `rules` sections. Other commands use the default config when it cannot be loaded.
+    fn requires_config(&self) -> bool {
+        matches!(
+            self,
+            Command::Rebalance
+                | Command::Profile { .. }
+                | Command::ApplyRules
+                | Command::EnsureSpaces { count: None }
+        )
+    }
 }
 
 fn main() -> anyhow::Result<()> {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
     let cli = Cli::parse();
+    let config = match Config::load(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(error) if !cli.command.requires_config() => {
+            log::warn!(
+                "{:?}",
+                anyhow::Error::new(error).context("Cannot load config. Using the default config")
+            );
+            Config::default()
+        }
+        Err(error) => return Err(error).context("Cannot load config"),
+    };
 
     match cli.command {
         Command::FocusSpace {
             space_specifier,
             target_space_options,
             destroy_empty_background_spaces,
+            skip_native_fullscreen,
         } => {
+            if destroy_empty_background_spaces {
+                destroy_spaces::record_visible_spaces(&config.destroy_spaces)?;
+            }
+
             if let Some(next_or_previous) = space_specifier.next_or_previous {
-                focus_next_or_previous_space(next_or_previous)?;
+                focus_next_or_previous_space(next_or_previous, skip_native_fullscreen)?;
             } else if let Some(label_prefix) = space_specifier.label_prefix {
                 focus_space_by_label(&label_prefix, target_space_options.create_if_not_found)
-                    .and_then(|_| reorder_spaces_by_stable_indexes())?;
+                    .and_then(|_| {
+                        reorder_spaces_by_stable_indexes(&config.reorder, &config.placeholders)
+                    })?;
             } else if let Some(stable_index) = space_specifier.stable_index {
                 let label_prefix = Space::label(stable_index, None);
                 focus_space_by_label(&label_prefix, target_space_options.create_if_not_found)
-                    .and_then(|_| reorder_spaces_by_stable_indexes())?;
+                    .and_then(|_| {
+                        reorder_spaces_by_stable_indexes(&config.reorder, &config.placeholders)
+                    })?;
             } else {
                 unreachable!("Some space specifier is required");
             }
 
             if destroy_empty_background_spaces {
-                destroy_spaces::destroy_empty_background_spaces()?;
+                destroy_spaces::destroy_empty_background_spaces(&config.destroy_spaces)?;
             }
 
             Ok(())
         }
-        Command::FocusWindow { direction } => focus_window_in_direction(direction),
+        Command::FocusWindow {
+            window_specifier,
+            wrap_options,
+        } => match window_specifier.target {
+            Some(FocusWindowTarget::Direction(direction)) => focus_window_in_direction(
+                direction,
+                &config.stack,
+                &wrap_options.apply_to(&config.focus),
+            ),
+            Some(FocusWindowTarget::NextOrPrevious(next_or_previous)) => {
+                focus_next_or_previous_window(next_or_previous)
+            }
+            None => focus_window_by_criteria(&WindowCriteria {
+                app: window_specifier.app,
+                title_regex: window_specifier.title_regex,
+            }),
+        },
+        Command::FocusDisplay {
+            target,
+            wrap_options,
+        } => focus_display(
+            &target.with_display_names(&config),
+            &wrap_options.apply_to(&config.focus),
+        ),
         Command::MoveSpace {
-            direction,
-            create_extra_space_if_last_on_display,
-        } => move_space_in_direction(direction, create_extra_space_if_last_on_display)
-            .and_then(|_| reorder_spaces_by_stable_indexes()),
-        Command::LabelSpaces => label_spaces().and_then(|_| reorder_spaces_by_stable_indexes()),
-        Command::ReorderByStableIndexes => reorder_spaces_by_stable_indexes(),
-        Command::SetLabel(args) => {
-            set_space_label(args).and_then(|_| reorder_spaces_by_stable_indexes())
+            target,
+            options,
+            wrap_options,
+        } => move_space_to_display(
+            &target.with_display_names(&config),
+            &options,
+            &wrap_options.apply_to(&config.focus),
+        )
+        .and_then(|_| clean_up_placeholder_spaces(config.placeholders.cleanup))
+        .and_then(|_| {
+            reorder_spaces_by_stable_indexes(
+                &config.reorder.within_displays(),
+                &config.placeholders,
+            )
+        }),
+        Command::SwapDisplays {
+            target,
+            wrap_options,
+        } => swap_displays(
+            target
+                .map(|target| target.with_display_names(&config))
+                .as_ref(),
+            &wrap_options.apply_to(&config.focus),
+        )
+        .and_then(|_| clean_up_placeholder_spaces(config.placeholders.cleanup))
+        .and_then(|_| {
+            reorder_spaces_by_stable_indexes(
+                &config.reorder.within_displays(),
+                &config.placeholders,
+            )
+        }),
+        Command::EnsureSpaces { count } => ensure_spaces(count, &config)
+            .and_then(|_| reorder_spaces_by_stable_indexes(&config.reorder, &config.placeholders)),
+        Command::LabelSpaces => label_spaces()
+            .and_then(|_| reorder_spaces_by_stable_indexes(&config.reorder, &config.placeholders)),
+        Command::ReorderByStableIndexes {
+            across_displays,
+            no_across_displays,
+            unlabeled,
+        } => {
+            let mut reorder_config = config.reorder.clone();
+            if across_displays {
+                reorder_config.across_displays = true;
+            } else if no_across_displays {
+                reorder_config.across_displays = false;
+            }
+            if let Some(unlabeled) = unlabeled {
+                reorder_config.unlabeled = unlabeled;
+            }
+
+            reorder_spaces_by_stable_indexes(&reorder_config, &config.placeholders)
         }
+        Command::SetLabel(args) => set_space_label(args)
+            .and_then(|_| reorder_spaces_by_stable_indexes(&config.reorder, &config.placeholders)),
         Command::MoveWindow { space_specifier } => match space_specifier {
             MoveWindowSpaceSpecifier::ToSpace {
                 stable_space_index,
                 target_space_options,
             } => move_window_to_space(stable_space_index, target_space_options.create_if_not_found)
-                .and_then(|_| reorder_spaces_by_stable_indexes()),
-            MoveWindowSpaceSpecifier::InDirection { direction } => {
-                move_window_in_direction(direction)
-            }
+                .and_then(|_| {
+                    reorder_spaces_by_stable_indexes(&config.reorder, &config.placeholders)
+                }),
+            MoveWindowSpaceSpecifier::InDirection {
+                direction,
+                wrap_options,
+            } => move_window_in_direction(direction, &wrap_options.apply_to(&config.focus)),
+            MoveWindowSpaceSpecifier::ToDisplay {
+                target,
+                wrap_options,
+            } => move_window_to_display(
+                &target.with_display_names(&config),
+                &wrap_options.apply_to(&config.focus),
+            ),
+        },
+        Command::Rebalance => rebalance_spaces(&config)
+            .and_then(|_| clean_up_placeholder_spaces(config.placeholders.cleanup))
+            .and_then(|_| {
+                reorder_spaces_by_stable_indexes(
+                    &config.reorder.within_displays(),
+                    &config.placeholders,
+                )
+            }),
+        Command::Resize { direction, amount } => resize_window_in_direction(direction, amount),
+        Command::SwapWindow { direction } => swap_window_in_direction(direction, &config.focus),
+        Command::Layout {
+            stable_index,
+            layout,
+        } => set_space_layout(layout, stable_index),
+        Command::Stack { direction } => stack_window_in_direction(direction, &config.focus),
+        Command::Unstack => unstack_window(),
+        Command::Toggle { property, state } => {
+            toggle_window_property(property, state, &config.float)
+        }
+        Command::Profile { command } => match command {
+            ProfileCommand::Apply { name } => apply_profile(name.as_deref(), &config)
+                .and_then(|_| clean_up_placeholder_spaces(config.placeholders.cleanup))
+                .and_then(|_| {
+                    reorder_spaces_by_stable_indexes(
+                        &config.reorder.within_displays(),
+                        &config.placeholders,
+                    )
+                }),
+            ProfileCommand::Detect => print_detected_profile(&config),
         },
+        Command::ApplyRules => apply_window_rules(&config.rules)
+            .and_then(|_| reorder_spaces_by_stable_indexes(&config.reorder, &config.placeholders)),
+        Command::RaiseOrRun {
+            app,
+            title_regex,
+            cmd,
+            wait_timeout,
+            stable_index,
+        } => raise_or_run(
+            &WindowCriteria {
+                app: Some(app),
+                title_regex,
+            },
+            &cmd,
+            wait_timeout.map(Duration::from_secs),
+            stable_index,
+        )
+        .and_then(|_| reorder_spaces_by_stable_indexes(&config.reorder, &config.placeholders)),
     }
     .and_then(|_| simple_bar::update().context("Cannot update simple-bar"))
 }
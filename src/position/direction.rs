@@ -2,13 +2,21 @@ use std::str::FromStr;
 
 use anyhow::anyhow;
 use clap::ValueEnum;
+use serde::Deserialize;
 
-#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(ValueEnum, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
 pub enum Direction {
     North,
     West,
     South,
     East,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+    /// The closest element in any direction.
+    Closest,
 }
 
 impl FromStr for Direction {
@@ -20,6 +28,11 @@ impl FromStr for Direction {
             "east" => Ok(Self::East),
             "north" => Ok(Self::North),
             "south" => Ok(Self::South),
+            "north-east" => Ok(Self::NorthEast),
+            "north-west" => Ok(Self::NorthWest),
+            "south-east" => Ok(Self::SouthEast),
+            "south-west" => Ok(Self::SouthWest),
+            "closest" => Ok(Self::Closest),
             _ => Err(anyhow!(format!("{s} is not a valid direction"))),
         }
     }
@@ -32,17 +45,92 @@ impl std::fmt::Display for Direction {
             Direction::North => write!(f, "north"),
             Direction::East => write!(f, "east"),
             Direction::West => write!(f, "west"),
+            Direction::NorthEast => write!(f, "north-east"),
+            Direction::NorthWest => write!(f, "north-west"),
+            Direction::SouthEast => write!(f, "south-east"),
+            Direction::SouthWest => write!(f, "south-west"),
+            Direction::Closest => write!(f, "closest"),
         }
     }
 }
 
 impl Direction {
+    /// There is no opposite of [`Direction::Closest`].
+    pub fn into_opposite(self) -> Option<Self> {
+        match self {
+            Direction::South => Some(Direction::North),
+            Direction::North => Some(Direction::South),
+            Direction::East => Some(Direction::West),
+            Direction::West => Some(Direction::East),
+            Direction::NorthEast => Some(Direction::SouthWest),
+            Direction::NorthWest => Some(Direction::SouthEast),
+            Direction::SouthEast => Some(Direction::NorthWest),
+            Direction::SouthWest => Some(Direction::NorthEast),
+            Direction::Closest => None,
+        }
+    }
+
+    /// North, west, south or east. Yabai only supports these directions in its commands.
+    pub fn is_cardinal(self) -> bool {
+        matches!(
+            self,
+            Direction::North | Direction::West | Direction::South | Direction::East
+        )
+    }
+}
+
+/// North, west, south or east. Yabai only supports these directions in its commands.
+#[derive(ValueEnum, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CardinalDirection {
+    North,
+    West,
+    South,
+    East,
+}
+
+impl From<CardinalDirection> for Direction {
+    fn from(value: CardinalDirection) -> Self {
+        match value {
+            CardinalDirection::North => Direction::North,
+            CardinalDirection::West => Direction::West,
+            CardinalDirection::South => Direction::South,
+            CardinalDirection::East => Direction::East,
+        }
+    }
+}
+
+impl TryFrom<Direction> for CardinalDirection {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Direction) -> Result<Self, Self::Error> {
+        match value {
+            Direction::North => Ok(CardinalDirection::North),
+            Direction::West => Ok(CardinalDirection::West),
+            Direction::South => Ok(CardinalDirection::South),
+            Direction::East => Ok(CardinalDirection::East),
+            Direction::NorthEast
+            | Direction::NorthWest
+            | Direction::SouthEast
+            | Direction::SouthWest
+            | Direction::Closest => Err(anyhow!("{value} is not a cardinal direction")),
+        }
+    }
+}
+
+impl std::fmt::Display for CardinalDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Direction::from(*self).fmt(f)
+    }
+}
+
+impl CardinalDirection {
     pub fn into_opposite(self) -> Self {
         match self {
-            Direction::South => Direction::North,
-            Direction::North => Direction::South,
-            Direction::East => Direction::West,
-            Direction::West => Direction::East,
+            CardinalDirection::North => CardinalDirection::South,
+            CardinalDirection::West => CardinalDirection::East,
+            CardinalDirection::South => CardinalDirection::North,
+            CardinalDirection::East => CardinalDirection::West,
         }
     }
 }
@@ -1,26 +1,146 @@
 mod direction;
+mod scoring;
 mod vector_2d;
 
-pub use direction::Direction;
+pub use direction::{CardinalDirection, Direction};
 use log::trace;
+pub use scoring::ScoringStrategy;
 
-use self::vector_2d::{find_closest_in_direction, get_candidates_in_direction, Vector2D};
+use self::vector_2d::{find_farthest_in_direction, get_candidates_in_direction, Vector2D};
 use crate::yabai::transport::Frame;
 
 pub fn get_element_to_focus<'a, T: std::fmt::Debug + AsRef<Frame>>(
     focused_frame: &Frame,
     elements: &'a [T],
     direction: Direction,
+    scoring: ScoringStrategy,
 ) -> Option<&'a T> {
-    trace!("Looking for candidates to focus in direction {direction:?} from {focused_frame:?}");
-    let candidates_in_direction =
-        get_candidates_in_direction(focused_frame, elements.iter(), direction);
-    trace!("Found candidates: {candidates_in_direction:#?}");
+    trace!("Looking for candidates to focus in direction {direction:?} from {focused_frame:?} using {scoring:?}");
 
-    find_closest_in_direction(
+    scoring.choose(focused_frame, elements, direction)
+}
+
+/// Finds the element to wrap around to when there is nothing in `direction`, like i3's focus
+/// wrapping. It is the farthest element in the opposite direction.
+///
+/// There is nothing to wrap around to in the [`Direction::Closest`] direction.
+pub fn get_element_to_wrap_to<'a, T: std::fmt::Debug + AsRef<Frame>>(
+    focused_frame: &Frame,
+    elements: &'a [T],
+    direction: Direction,
+) -> Option<&'a T> {
+    let opposite_direction = direction.into_opposite()?;
+    let candidates =
+        get_candidates_in_direction(focused_frame, elements.iter(), opposite_direction);
+    trace!("Wrapping around in direction {direction:?}. Found candidates: {candidates:#?}");
+
+    find_farthest_in_direction(
         Vector2D::from_frame_center(focused_frame),
-        candidates_in_direction.into_iter(),
-        |window| Vector2D::from_frame_center(window.as_ref()),
-        direction.into(),
+        candidates.into_iter(),
+        |element| Vector2D::from_frame_center(element.as_ref()),
+        opposite_direction.into(),
     )
 }
+
+/// Layouts of frames to test choosing elements in a direction.
+#[cfg(test)]
+mod fixtures {
+    use super::*;
+
+    pub(super) fn frame(x: f32, y: f32, width: f32, height: f32) -> Frame {
+        Frame {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub(super) struct Layout<Case> {
+        pub(super) name: &'static str,
+        pub(super) focused: Frame,
+        pub(super) others: Vec<Frame>,
+        /// Index in `others` of the expected element for each case.
+        pub(super) expected: Vec<(Case, Option<usize>)>,
+    }
+
+    impl<Case: std::fmt::Debug + Copy> Layout<Case> {
+        pub(super) fn assert_chooses<'a>(&'a self, choose: impl Fn(Case) -> Option<&'a Frame>) {
+            for (case, expected) in &self.expected {
+                let chosen = choose(*case).map(|chosen| {
+                    self.others
+                        .iter()
+                        .position(|other| std::ptr::eq(other, chosen))
+                        .unwrap()
+                });
+
+                assert_eq!(*expected, chosen, "{case:?} in layout \"{}\"", self.name);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        fixtures::{frame, Layout},
+        *,
+    };
+
+    fn layouts() -> Vec<Layout<Direction>> {
+        vec![
+            Layout {
+                name: "top-right window of a 3x3 grid",
+                focused: frame(200.0, 0.0, 100.0, 100.0),
+                others: vec![
+                    frame(0.0, 0.0, 100.0, 100.0),
+                    frame(100.0, 0.0, 100.0, 100.0),
+                    frame(0.0, 100.0, 100.0, 100.0),
+                    frame(100.0, 100.0, 100.0, 100.0),
+                    frame(200.0, 100.0, 100.0, 100.0),
+                    frame(0.0, 200.0, 100.0, 100.0),
+                    frame(100.0, 200.0, 100.0, 100.0),
+                    frame(200.0, 200.0, 100.0, 100.0),
+                ],
+                expected: vec![
+                    (Direction::East, Some(0)),
+                    (Direction::North, Some(7)),
+                    (Direction::NorthEast, Some(5)),
+                    (Direction::West, None),
+                    (Direction::South, None),
+                    (Direction::SouthWest, None),
+                    (Direction::Closest, None),
+                ],
+            },
+            Layout {
+                name: "equally far windows at different heights",
+                focused: frame(0.0, 0.0, 100.0, 100.0),
+                others: vec![
+                    frame(100.0, 0.0, 100.0, 100.0),
+                    frame(200.0, -50.0, 100.0, 100.0),
+                    frame(200.0, 0.0, 100.0, 100.0),
+                ],
+                expected: vec![(Direction::West, Some(2)), (Direction::East, None)],
+            },
+            Layout {
+                name: "staggered displays",
+                focused: frame(-1440.0, 1080.0, 1440.0, 900.0),
+                others: vec![frame(0.0, 0.0, 1920.0, 1080.0)],
+                expected: vec![
+                    (Direction::SouthWest, Some(0)),
+                    (Direction::West, None),
+                    (Direction::South, None),
+                ],
+            },
+        ]
+    }
+
+    #[test]
+    fn wraps_to_expected_elements_in_layouts() {
+        for layout in layouts() {
+            layout.assert_chooses(|direction| {
+                get_element_to_wrap_to(&layout.focused, &layout.others, direction)
+            });
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use serde::Deserialize;
+
+use crate::yabai::transport::Frame;
+
+use super::{
+    vector_2d::{find_closest, find_closest_in_direction, get_candidates_in_direction, Vector2D},
+    Direction,
+};
+
+/// How the element to focus in a given direction is chosen.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScoringStrategy {
+    /// Among elements that overlap the focused element, the one with the lowest distance between
+    /// centers divided by the cosine of the angle to the direction. Favors aligned elements.
+    #[default]
+    DistanceOverCosine,
+
+    /// Like i3, the element with the closest edge, preferring the one that overlaps the focused
+    /// element the most. Diagonal directions use `distance-over-cosine`.
+    EdgeOverlap,
+
+    /// Among elements that overlap the focused element, the one with the closest center.
+    CenterDistance,
+
+    /// The element with the closest center, within `max-angle` degrees of the direction.
+    /// Elements do not need to overlap the focused element.
+    Cone {
+        #[serde(rename = "max-angle")]
+        max_angle: f32,
+    },
+}
+
+impl ScoringStrategy {
+    pub(super) fn choose<'a, T: std::fmt::Debug + AsRef<Frame>>(
+        self,
+        focused_frame: &Frame,
+        elements: &'a [T],
+        direction: Direction,
+    ) -> Option<&'a T> {
+        let center = Vector2D::from_frame_center(focused_frame);
+        let element_center = |element: &&T| Vector2D::from_frame_center((*element).as_ref());
+        let candidates_in_direction = || {
+            let candidates = get_candidates_in_direction(focused_frame, elements.iter(), direction);
+            log::trace!("Found candidates: {candidates:#?}");
+            candidates.into_iter()
+        };
+
+        if direction == Direction::Closest {
+            return find_closest(center, candidates_in_direction(), element_center);
+        }
+
+        match self {
+            ScoringStrategy::DistanceOverCosine => find_closest_in_direction(
+                center,
+                candidates_in_direction(),
+                element_center,
+                direction.into(),
+            ),
+            ScoringStrategy::EdgeOverlap if direction.is_cardinal() => candidates_in_direction()
+                .min_by(|a, b| {
+                    let key = |element: &T| {
+                        let frame = element.as_ref();
+                        let distance = (Vector2D::from_frame_center(frame) - center).length();
+
+                        (
+                            edge_gap(focused_frame, frame, direction),
+                            -overlap(focused_frame, frame, direction),
+                            distance,
+                        )
+                    };
+
+                    let (gap_a, overlap_a, distance_a) = key(a);
+                    let (gap_b, overlap_b, distance_b) = key(b);
+
+                    f32::total_cmp(&gap_a, &gap_b)
+                        .then(f32::total_cmp(&overlap_a, &overlap_b))
+                        .then(f32::total_cmp(&distance_a, &distance_b))
+                }),
+            ScoringStrategy::EdgeOverlap => {
+                ScoringStrategy::DistanceOverCosine.choose(focused_frame, elements, direction)
+            }
+            ScoringStrategy::CenterDistance => {
+                find_closest(center, candidates_in_direction(), element_center)
+            }
+            ScoringStrategy::Cone { max_angle } => {
+                let direction_vector = Vector2D::from(direction);
+                let min_cos = max_angle.clamp(0.0, 90.0).to_radians().cos();
+
+                let candidates = elements.iter().filter(|element| {
+                    let element_direction = element_center(element) - center;
+                    let length = element_direction.length();
+
+                    length > f32::EPSILON
+                        && Vector2D::dot(direction_vector, element_direction) / length
+                            >= min_cos - f32::EPSILON
+                });
+
+                find_closest(center, candidates, element_center)
+            }
+        }
+    }
+}
+
+/// Distance between the facing edges of the frames in a cardinal direction.
+fn edge_gap(focused_frame: &Frame, frame: &Frame, direction: Direction) -> f32 {
+    match direction {
+        Direction::North => focused_frame.y - (frame.y + frame.height),
+        Direction::South => frame.y - (focused_frame.y + focused_frame.height),
+        Direction::West => focused_frame.x - (frame.x + frame.width),
+        Direction::East => frame.x - (focused_frame.x + focused_frame.width),
+        _ => 0.0,
+    }
+}
+
+/// Length of the overlap of the frames, perpendicular to a cardinal direction.
+fn overlap(focused_frame: &Frame, frame: &Frame, direction: Direction) -> f32 {
+    let overlap_1d = |start_a: f32, length_a: f32, start_b: f32, length_b: f32| {
+        let start = start_a.max(start_b);
+        let end = (start_a + length_a).min(start_b + length_b);
+        (end - start).max(0.0)
+    };
+
+    match direction {
+        Direction::North | Direction::South => {
+            overlap_1d(focused_frame.x, focused_frame.width, frame.x, frame.width)
+        }
+        Direction::West | Direction::East => {
+            overlap_1d(focused_frame.y, focused_frame.height, frame.y, frame.height)
+        }
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::fixtures::{frame, Layout};
+
+    const CONE: ScoringStrategy = ScoringStrategy::Cone { max_angle: 30.0 };
+
+    fn layouts() -> Vec<(Direction, Layout<ScoringStrategy>)> {
+        vec![
+            (
+                Direction::East,
+                Layout {
+                    name: "tall window next to several short ones",
+                    focused: frame(0.0, 0.0, 100.0, 300.0),
+                    others: vec![
+                        frame(100.0, 0.0, 100.0, 100.0),
+                        frame(100.0, 100.0, 100.0, 100.0),
+                        frame(100.0, 200.0, 100.0, 100.0),
+                    ],
+                    expected: vec![
+                        (ScoringStrategy::DistanceOverCosine, Some(1)),
+                        (ScoringStrategy::EdgeOverlap, Some(1)),
+                        (ScoringStrategy::CenterDistance, Some(1)),
+                        (CONE, Some(1)),
+                    ],
+                },
+            ),
+            (
+                Direction::East,
+                Layout {
+                    name: "adjacent tall window barely overlapping, aligned window further away",
+                    focused: frame(0.0, 0.0, 100.0, 100.0),
+                    others: vec![
+                        frame(110.0, 90.0, 100.0, 310.0),
+                        frame(200.0, 0.0, 100.0, 100.0),
+                    ],
+                    expected: vec![
+                        (ScoringStrategy::DistanceOverCosine, Some(1)),
+                        (ScoringStrategy::EdgeOverlap, Some(0)),
+                        (ScoringStrategy::CenterDistance, Some(1)),
+                        (CONE, Some(1)),
+                    ],
+                },
+            ),
+            (
+                Direction::East,
+                Layout {
+                    name: "close window at an angle, aligned window further away",
+                    focused: frame(0.0, 0.0, 100.0, 100.0),
+                    others: vec![
+                        frame(100.0, 95.0, 100.0, 200.0),
+                        frame(200.0, 0.0, 100.0, 100.0),
+                    ],
+                    expected: vec![
+                        (ScoringStrategy::DistanceOverCosine, Some(1)),
+                        (ScoringStrategy::EdgeOverlap, Some(0)),
+                        (ScoringStrategy::CenterDistance, Some(0)),
+                        (CONE, Some(1)),
+                    ],
+                },
+            ),
+            (
+                Direction::North,
+                Layout {
+                    name: "stacked short windows above a wide one",
+                    focused: frame(0.0, 200.0, 300.0, 100.0),
+                    others: vec![
+                        frame(0.0, 0.0, 100.0, 200.0),
+                        frame(100.0, 0.0, 200.0, 100.0),
+                        frame(100.0, 100.0, 200.0, 100.0),
+                    ],
+                    expected: vec![
+                        (ScoringStrategy::DistanceOverCosine, Some(2)),
+                        (ScoringStrategy::EdgeOverlap, Some(2)),
+                        (ScoringStrategy::CenterDistance, Some(2)),
+                        (CONE, Some(2)),
+                    ],
+                },
+            ),
+            (
+                Direction::North,
+                Layout {
+                    name: "staggered displays without overlap",
+                    focused: frame(-1440.0, 1080.0, 1440.0, 900.0),
+                    others: vec![frame(0.0, 0.0, 1920.0, 1080.0)],
+                    expected: vec![
+                        (ScoringStrategy::DistanceOverCosine, None),
+                        (ScoringStrategy::EdgeOverlap, None),
+                        (ScoringStrategy::CenterDistance, None),
+                        (ScoringStrategy::Cone { max_angle: 60.0 }, Some(0)),
+                    ],
+                },
+            ),
+            (
+                Direction::NorthEast,
+                Layout {
+                    name: "staggered displays in a diagonal direction",
+                    focused: frame(-1440.0, 1080.0, 1440.0, 900.0),
+                    others: vec![frame(0.0, 0.0, 1920.0, 1080.0)],
+                    expected: vec![
+                        (ScoringStrategy::DistanceOverCosine, Some(0)),
+                        (ScoringStrategy::EdgeOverlap, Some(0)),
+                        (ScoringStrategy::CenterDistance, Some(0)),
+                        (CONE, Some(0)),
+                    ],
+                },
+            ),
+            (
+                Direction::East,
+                Layout {
+                    name: "nothing in the direction",
+                    focused: frame(100.0, 0.0, 100.0, 100.0),
+                    others: vec![frame(0.0, 0.0, 100.0, 100.0)],
+                    expected: vec![
+                        (ScoringStrategy::DistanceOverCosine, None),
+                        (ScoringStrategy::EdgeOverlap, None),
+                        (ScoringStrategy::CenterDistance, None),
+                        (CONE, None),
+                    ],
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn chooses_expected_elements_in_layouts() {
+        for (direction, layout) in layouts() {
+            layout.assert_chooses(|strategy| {
+                strategy.choose(&layout.focused, &layout.others, direction)
+            });
+        }
+    }
+
+    #[test]
+    fn parses_strategies() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            scoring: ScoringStrategy,
+        }
+
+        let parse = |toml| toml::from_str::<Wrapper>(toml).unwrap().scoring;
+
+        assert_eq!(
+            ScoringStrategy::EdgeOverlap,
+            parse("scoring = \"edge-overlap\"")
+        );
+        assert_eq!(
+            ScoringStrategy::Cone { max_angle: 45.0 },
+            parse("scoring = { cone = { max-angle = 45 } }")
+        );
+    }
+}
@@ -27,11 +27,31 @@ impl Vector2D {
 
 impl From<Direction> for Vector2D {
     fn from(value: Direction) -> Self {
+        const DIAGONAL: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
         match value {
             Direction::North => Self { x: 0.0, y: -1.0 },
             Direction::East => Self { x: 1.0, y: 0.0 },
             Direction::South => Self { x: 0.0, y: 1.0 },
             Direction::West => Self { x: -1.0, y: 0.0 },
+            Direction::NorthEast => Self {
+                x: DIAGONAL,
+                y: -DIAGONAL,
+            },
+            Direction::NorthWest => Self {
+                x: -DIAGONAL,
+                y: -DIAGONAL,
+            },
+            Direction::SouthEast => Self {
+                x: DIAGONAL,
+                y: DIAGONAL,
+            },
+            Direction::SouthWest => Self {
+                x: -DIAGONAL,
+                y: DIAGONAL,
+            },
+            // NOTE: there is no heading. Use `find_closest` instead
+            Direction::Closest => Self { x: 0.0, y: 0.0 },
         }
     }
 }
@@ -95,6 +115,57 @@ where
         .next()
 }
 
+/// Finds the candidate whose center is the closest to `center`, regardless of the direction.
+pub fn find_closest<T, Iter, F>(
+    center: Vector2D,
+    candidates: Iter,
+    candidate_to_vector: F,
+) -> Option<T>
+where
+    F: Fn(&T) -> Vector2D,
+    Iter: Iterator<Item = T>,
+{
+    candidates
+        .map(|candidate| {
+            let distance = (candidate_to_vector(&candidate) - center).length();
+            (candidate, distance)
+        })
+        .min_by(|(_, distance_a), (_, distance_b)| f32::total_cmp(distance_a, distance_b))
+        .map(|(candidate, _)| candidate)
+}
+
+/// Finds the candidate that is the farthest from `center` along `direction`.
+///
+/// Among equally far candidates, the one closest to the line going through `center` wins.
+pub fn find_farthest_in_direction<T, Iter, F>(
+    center: Vector2D,
+    candidates: Iter,
+    candidate_to_vector: F,
+    direction: Vector2D,
+) -> Option<T>
+where
+    F: Fn(&T) -> Vector2D,
+    Iter: Iterator<Item = T>,
+{
+    let perpendicular = Vector2D {
+        x: -direction.y,
+        y: direction.x,
+    };
+
+    candidates
+        .map(|candidate| {
+            let candidate_direction = candidate_to_vector(&candidate) - center;
+            let distance = Vector2D::dot(direction, candidate_direction);
+            let offset = Vector2D::dot(perpendicular, candidate_direction).abs();
+
+            (candidate, distance, offset)
+        })
+        .max_by(|(_, distance_a, offset_a), (_, distance_b, offset_b)| {
+            f32::total_cmp(distance_a, distance_b).then(f32::total_cmp(offset_b, offset_a))
+        })
+        .map(|(candidate, _, _)| candidate)
+}
+
 pub fn get_candidates_in_direction<'a, 'b, T, Iter>(
     frame: &'a Frame,
     candidates: Iter,
@@ -104,15 +175,27 @@ where
     T: AsRef<Frame>,
     Iter: Iterator<Item = &'b T>,
 {
-    let check_frame_direction = match direction {
+    // NOTE: diagonal candidates must lie entirely in the quadrant, so they do not overlap the
+    // frame in either axis
+    let check_frame_direction: fn(&Frame, &Frame) -> bool = match direction {
         Direction::West => Frame::is_west_of,
         Direction::East => Frame::is_east_of,
         Direction::North => Frame::is_north_of,
         Direction::South => Frame::is_south_of,
+        Direction::NorthEast => |other, frame| other.is_north_of(frame) && other.is_east_of(frame),
+        Direction::NorthWest => |other, frame| other.is_north_of(frame) && other.is_west_of(frame),
+        Direction::SouthEast => |other, frame| other.is_south_of(frame) && other.is_east_of(frame),
+        Direction::SouthWest => |other, frame| other.is_south_of(frame) && other.is_west_of(frame),
+        Direction::Closest => |other, frame| other != frame,
     };
-    let check_overlap = match direction {
+    let check_overlap: fn(&Frame, &Frame) -> bool = match direction {
         Direction::West | Direction::East => Frame::overlaps_vertically,
         Direction::North | Direction::South => Frame::overlaps_horizontally,
+        Direction::NorthEast
+        | Direction::NorthWest
+        | Direction::SouthEast
+        | Direction::SouthWest
+        | Direction::Closest => |_, _| true,
     };
 
     candidates
@@ -193,4 +276,96 @@ mod tests {
 
         assert_eq!(Some(&closest_vector), found_closest_vector);
     }
+
+    #[test]
+    fn find_farthest() {
+        let candidates = [
+            Vector2D { x: 5.0, y: 2.0 },
+            Vector2D { x: 17.2, y: 1.0 },
+            Vector2D { x: 17.2, y: -3.0 },
+            Vector2D { x: -20.0, y: 0.0 },
+        ];
+
+        let found = find_farthest_in_direction(
+            Vector2D { x: 0.0, y: 0.0 },
+            candidates.iter(),
+            |&&vector| vector,
+            Direction::East.into(),
+        );
+
+        assert_eq!(Some(&candidates[1]), found);
+    }
+
+    #[test]
+    fn find_farthest_in_each_direction() {
+        let candidates = [
+            Vector2D { x: 5.0, y: 2.0 },
+            Vector2D { x: 17.2, y: 1.0 },
+            Vector2D { x: 17.2, y: -3.0 },
+            Vector2D { x: -20.0, y: 0.0 },
+        ];
+        let expected = [
+            (Direction::East, 1),
+            (Direction::West, 3),
+            (Direction::North, 2),
+            (Direction::South, 0),
+            (Direction::NorthEast, 2),
+            (Direction::SouthWest, 3),
+        ];
+
+        for (direction, expected) in expected {
+            let found = find_farthest_in_direction(
+                Vector2D { x: 0.0, y: 0.0 },
+                candidates.iter(),
+                |&&vector| vector,
+                direction.into(),
+            );
+
+            assert_eq!(Some(&candidates[expected]), found, "{direction:?}");
+        }
+
+        let found = find_farthest_in_direction(
+            Vector2D { x: 0.0, y: 0.0 },
+            std::iter::empty::<Vector2D>(),
+            |&vector| vector,
+            Direction::East.into(),
+        );
+        assert_eq!(None, found);
+    }
+
+    #[test]
+    fn filter_candidates_in_diagonal_direction() {
+        let frame = |x, y| Frame {
+            x,
+            y,
+            width: 100.0,
+            height: 100.0,
+        };
+
+        // Laptop display below and left of an external display
+        let laptop = frame(0.0, 100.0);
+        let frames = [frame(100.0, 0.0), frame(0.0, 0.0), frame(100.0, 100.0)];
+
+        let candidates = get_candidates_in_direction(&laptop, frames.iter(), Direction::NorthEast);
+        assert_eq!(vec![&frames[0]], candidates);
+
+        let candidates = get_candidates_in_direction(&laptop, frames.iter(), Direction::SouthWest);
+        assert!(candidates.is_empty());
+    }
+
+    #[test]
+    fn find_closest_in_any_direction() {
+        let candidates = [
+            Vector2D { x: 5.0, y: 2.0 },
+            Vector2D { x: -1.0, y: -1.0 },
+            Vector2D { x: 0.0, y: 3.0 },
+        ];
+
+        let found =
+            super::find_closest(Vector2D { x: 0.0, y: 0.0 }, candidates.iter(), |&&vector| {
+                vector
+            });
+
+        assert_eq!(Some(&candidates[1]), found);
+    }
 }
@@ -0,0 +1,118 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{label::space::StableSpaceIndex, yabai::transport::SpaceType};
+
+/// Information that yabaictl remembers between invocations.
+///
+/// Unlike the config, the state is written by yabaictl itself.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct State {
+    /// Layouts chosen with `yabaictl layout`, reapplied when the space is created again.
+    pub space_layouts: BTreeMap<StableSpaceIndex, SpaceType>,
+
+    /// UUIDs of empty spaces created so that the last space of a display could be sent to another
+    /// display.
+    pub placeholder_spaces: BTreeSet<String>,
+
+    /// When spaces were last seen visible, in seconds since the UNIX epoch, keyed by their UUIDs.
+    pub spaces_last_visible: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Error)]
+pub enum StateError {
+    #[error("Cannot determine the state file path. Set $XDG_STATE_HOME or $HOME")]
+    UnknownPath,
+
+    #[error("Cannot access state file {path:?}")]
+    IO {
+        path: PathBuf,
+
+        #[source]
+        cause: io::Error,
+    },
+
+    #[error("Cannot parse state file {path:?}")]
+    Parse {
+        path: PathBuf,
+
+        #[source]
+        cause: serde_json::Error,
+    },
+}
+
+impl State {
+    /// Reads the state. A missing state file results in the default state.
+    pub fn load() -> Result<Self, StateError> {
+        let path = state_path().ok_or(StateError::UnknownPath)?;
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(cause) => return Err(StateError::IO { path, cause }),
+        };
+
+        serde_json::from_str(&contents).map_err(|cause| StateError::Parse { path, cause })
+    }
+
+    pub fn save(&self) -> Result<(), StateError> {
+        let path = state_path().ok_or(StateError::UnknownPath)?;
+
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory).map_err(|cause| StateError::IO {
+                path: path.clone(),
+                cause,
+            })?;
+        }
+
+        let contents = serde_json::to_string_pretty(self).expect("State can be serialized");
+        std::fs::write(&path, contents).map_err(|cause| StateError::IO { path, cause })
+    }
+
+    /// Loads the state, applies `update` to it and saves it.
+    pub fn update<T>(update: impl FnOnce(&mut Self) -> T) -> Result<T, StateError> {
+        let mut state = Self::load()?;
+        let result = update(&mut state);
+        state.save()?;
+
+        Ok(result)
+    }
+}
+
+/// `$XDG_STATE_HOME/yabaictl/state.json`, falling back to `~/.local/state/yabaictl/state.json`.
+fn state_path() -> Option<PathBuf> {
+    let state_home = std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+        })?;
+
+    Some(state_home.join("yabaictl").join("state.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_space_layouts() {
+        let mut state = State::default();
+        state
+            .space_layouts
+            .insert("3".parse().unwrap(), SpaceType::Stack);
+
+        let serialized = serde_json::to_string(&state).unwrap();
+        let deserialized: State = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(state.space_layouts, deserialized.space_layouts);
+    }
+}
@@ -0,0 +1,82 @@
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+
+use crate::yabai::transport::Window;
+
+/// Conditions that a window must meet, like i3's criteria (`[class="..."]`).
+///
+/// Empty criteria match every window.
+#[derive(Debug, Clone, Default)]
+pub struct WindowCriteria {
+    /// Application name, compared case-insensitively.
+    pub app: Option<String>,
+    pub title_regex: Option<Regex>,
+}
+
+impl WindowCriteria {
+    pub fn matches(&self, window: &Window) -> bool {
+        let app_matches = self
+            .app
+            .as_deref()
+            .is_none_or(|app| app.eq_ignore_ascii_case(&window.app));
+        let title_matches = self
+            .title_regex
+            .as_ref()
+            .is_none_or(|title_regex| title_regex.is_match(&window.title));
+
+        app_matches && title_matches
+    }
+}
+
+impl std::fmt::Display for WindowCriteria {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        if let Some(app) = &self.app {
+            write!(f, "app=\"{app}\"")?;
+        }
+        if let Some(title_regex) = &self.title_regex {
+            if self.app.is_some() {
+                write!(f, " ")?;
+            }
+            write!(f, "title=\"{title_regex}\"")?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Deserializes an optional regular expression from a string, for use with
+/// `#[serde(deserialize_with)]`.
+pub(crate) fn deserialize_optional_regex<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Regex>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|pattern| Regex::new(&pattern).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yabai::transport::{fixtures, Frame};
+
+    fn window(app: &str, title: &str) -> Window {
+        Window {
+            app: app.to_owned(),
+            title: title.to_owned(),
+            ..fixtures::window(1, Frame::default())
+        }
+    }
+
+    #[test]
+    fn matches_windows() {
+        let criteria = WindowCriteria {
+            app: Some("slack".to_owned()),
+            title_regex: Some(Regex::new("^general").unwrap()),
+        };
+
+        assert!(criteria.matches(&window("Slack", "general | Workspace")));
+        assert!(!criteria.matches(&window("Slack", "random | Workspace")));
+        assert!(!criteria.matches(&window("Firefox", "general")));
+        assert!(WindowCriteria::default().matches(&window("Firefox", "general")));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::position::Direction;
 
-use super::transport::{Display, DisplayIndex, Space, SpaceIndex, Window, WindowId};
+use super::transport::{Display, DisplayIndex, Space, SpaceIndex, SpaceType, Window, WindowId};
 
 pub trait YabaiCommand {
     type Output;
@@ -209,6 +209,7 @@ impl YabaiCommand for LabelSpace {
     fn parse_output(&self, _output: &str) -> Self::Output {}
 }
 
+/// Labels are the preferred selectors, but any yabai space selector works.
 pub struct MoveSpace {
     pub source_label: String,
     pub target_label: String,
@@ -231,6 +232,8 @@ impl YabaiCommand for MoveSpace {
 }
 
 pub struct MoveWindowToSpace {
+    /// Window to move. Defaults to the focused window.
+    pub window_id: Option<WindowId>,
     pub target_space_label: String,
 }
 
@@ -238,28 +241,40 @@ impl YabaiCommand for MoveWindowToSpace {
     type Output = ();
 
     fn to_args(&self) -> Vec<String> {
-        vec![
-            "-m".to_string(),
-            "window".to_string(),
-            "--space".to_string(),
-            self.target_space_label.clone(),
-        ]
+        let mut args = vec!["-m".to_string(), "window".to_string()];
+
+        if let Some(window_id) = self.window_id {
+            args.push(window_id.to_string());
+        }
+
+        args.extend(["--space".to_string(), self.target_space_label.clone()]);
+
+        args
     }
 
     fn parse_output(&self, _output: &str) -> Self::Output {}
 }
 
-pub struct CreateSpace;
+pub struct CreateSpace {
+    /// Display to create the space on. Defaults to the display of the active space.
+    pub display_index: Option<DisplayIndex>,
+}
 
 impl YabaiCommand for CreateSpace {
     type Output = ();
 
     fn to_args(&self) -> Vec<String> {
-        vec![
+        let mut args = vec![
             "-m".to_string(),
             "space".to_string(),
             "--create".to_string(),
-        ]
+        ];
+
+        if let Some(display_index) = self.display_index {
+            args.push(display_index.to_string());
+        }
+
+        args
     }
 
     fn parse_output(&self, _output: &str) -> Self::Output {}
@@ -322,3 +337,243 @@ impl YabaiCommand for WarpWindow {
 
     fn parse_output(&self, _output: &str) -> Self::Output {}
 }
+
+/// Edge or corner of the window that is moved when resizing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizeHandle {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    /// Sets the absolute size of the window.
+    Absolute,
+}
+
+impl std::fmt::Display for ResizeHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResizeHandle::Top => write!(f, "top"),
+            ResizeHandle::Bottom => write!(f, "bottom"),
+            ResizeHandle::Left => write!(f, "left"),
+            ResizeHandle::Right => write!(f, "right"),
+            ResizeHandle::Absolute => write!(f, "abs"),
+        }
+    }
+}
+
+/// Resizes the focused window by moving `handle` by `dx` and `dy` pixels.
+pub struct ResizeWindow {
+    pub handle: ResizeHandle,
+    pub dx: i32,
+    pub dy: i32,
+}
+
+impl YabaiCommand for ResizeWindow {
+    type Output = ();
+
+    fn to_args(&self) -> Vec<String> {
+        vec![
+            "-m".to_string(),
+            "window".to_string(),
+            "--resize".to_string(),
+            format!("{}:{}:{}", self.handle, self.dx, self.dy),
+        ]
+    }
+
+    fn parse_output(&self, _output: &str) -> Self::Output {}
+}
+
+/// Swaps the position of two windows.
+pub struct SwapWindow {
+    pub window_id: WindowId,
+    pub other_window_id: WindowId,
+}
+
+impl YabaiCommand for SwapWindow {
+    type Output = ();
+
+    fn to_args(&self) -> Vec<String> {
+        vec![
+            "-m".to_string(),
+            "window".to_string(),
+            self.window_id.to_string(),
+            "--swap".to_string(),
+            self.other_window_id.to_string(),
+        ]
+    }
+
+    fn parse_output(&self, _output: &str) -> Self::Output {}
+}
+
+pub struct SetSpaceLayout {
+    pub index: SpaceIndex,
+    pub layout: SpaceType,
+}
+
+impl YabaiCommand for SetSpaceLayout {
+    type Output = ();
+
+    fn to_args(&self) -> Vec<String> {
+        vec![
+            "-m".to_string(),
+            "space".to_string(),
+            self.index.to_string(),
+            "--layout".to_string(),
+            self.layout.to_string(),
+        ]
+    }
+
+    fn parse_output(&self, _output: &str) -> Self::Output {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowToggle {
+    /// Changes the split type of the window's parent node in a BSP space.
+    Split,
+    Float,
+    Sticky,
+    /// Zooms the window to the size of its parent node.
+    ZoomParent,
+    /// Zooms the window to the size of the space.
+    ZoomFullscreen,
+    NativeFullscreen,
+    /// Picture-in-picture.
+    Pip,
+}
+
+impl std::fmt::Display for WindowToggle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowToggle::Split => write!(f, "split"),
+            WindowToggle::Float => write!(f, "float"),
+            WindowToggle::Sticky => write!(f, "sticky"),
+            WindowToggle::ZoomParent => write!(f, "zoom-parent"),
+            WindowToggle::ZoomFullscreen => write!(f, "zoom-fullscreen"),
+            WindowToggle::NativeFullscreen => write!(f, "native-fullscreen"),
+            WindowToggle::Pip => write!(f, "pip"),
+        }
+    }
+}
+
+pub struct ToggleWindow {
+    /// Window to toggle the property of. Defaults to the focused window.
+    pub window_id: Option<WindowId>,
+    pub toggle: WindowToggle,
+}
+
+impl YabaiCommand for ToggleWindow {
+    type Output = ();
+
+    fn to_args(&self) -> Vec<String> {
+        let mut args = vec!["-m".to_string(), "window".to_string()];
+
+        if let Some(window_id) = self.window_id {
+            args.push(window_id.to_string());
+        }
+
+        args.extend(["--toggle".to_string(), self.toggle.to_string()]);
+
+        args
+    }
+
+    fn parse_output(&self, _output: &str) -> Self::Output {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackWindowSelector {
+    Next,
+    Previous,
+}
+
+impl std::fmt::Display for StackWindowSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StackWindowSelector::Next => write!(f, "stack.next"),
+            StackWindowSelector::Previous => write!(f, "stack.prev"),
+        }
+    }
+}
+
+/// Focuses another window in the stack of the focused window.
+pub struct FocusStackWindow {
+    pub selector: StackWindowSelector,
+}
+
+impl YabaiCommand for FocusStackWindow {
+    type Output = ();
+
+    fn to_args(&self) -> Vec<String> {
+        vec![
+            "-m".to_string(),
+            "window".to_string(),
+            "--focus".to_string(),
+            self.selector.to_string(),
+        ]
+    }
+
+    fn parse_output(&self, _output: &str) -> Self::Output {}
+}
+
+/// Stacks `window_id` on top of `target_window_id`.
+pub struct StackWindow {
+    pub window_id: WindowId,
+    pub target_window_id: WindowId,
+}
+
+impl YabaiCommand for StackWindow {
+    type Output = ();
+
+    fn to_args(&self) -> Vec<String> {
+        vec![
+            "-m".to_string(),
+            "window".to_string(),
+            self.target_window_id.to_string(),
+            "--stack".to_string(),
+            self.window_id.to_string(),
+        ]
+    }
+
+    fn parse_output(&self, _output: &str) -> Self::Output {}
+}
+
+/// Moves the focused window so its top-left corner is at the given absolute position.
+pub struct MoveWindowAbsolute {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl YabaiCommand for MoveWindowAbsolute {
+    type Output = ();
+
+    fn to_args(&self) -> Vec<String> {
+        vec![
+            "-m".to_string(),
+            "window".to_string(),
+            "--move".to_string(),
+            format!("abs:{}:{}", self.x, self.y),
+        ]
+    }
+
+    fn parse_output(&self, _output: &str) -> Self::Output {}
+}
+
+/// Changes a global yabai setting, like `yabai -m config top_padding 10`.
+pub struct SetConfigValue {
+    pub key: &'static str,
+    pub value: String,
+}
+
+impl YabaiCommand for SetConfigValue {
+    type Output = ();
+
+    fn to_args(&self) -> Vec<String> {
+        vec![
+            "-m".to_string(),
+            "config".to_string(),
+            self.key.to_string(),
+            self.value.clone(),
+        ]
+    }
+
+    fn parse_output(&self, _output: &str) -> Self::Output {}
+}
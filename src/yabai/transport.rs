@@ -1,8 +1,8 @@
 use std::ops::Deref;
 
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize};
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
 pub struct Frame {
     pub x: f32,
     pub y: f32,
@@ -88,7 +88,7 @@ impl Deref for DisplayIndex {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct WindowId(pub u32);
 
 impl std::fmt::Display for WindowId {
@@ -110,6 +110,11 @@ pub struct Display {
     pub id: DisplayId,
     pub uuid: String,
     pub index: DisplayIndex,
+
+    /// Older yabai versions do not support display labels.
+    #[serde(default, deserialize_with = "deserialize_label")]
+    pub label: Option<String>,
+
     pub frame: Frame,
     pub spaces: Vec<SpaceIndex>,
 }
@@ -120,7 +125,7 @@ impl AsRef<Frame> for Display {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum SpaceType {
     BSP,
@@ -128,6 +133,16 @@ pub enum SpaceType {
     Float,
 }
 
+impl std::fmt::Display for SpaceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpaceType::BSP => write!(f, "bsp"),
+            SpaceType::Stack => write!(f, "stack"),
+            SpaceType::Float => write!(f, "float"),
+        }
+    }
+}
+
 fn deserialize_window_id_maybe_zero<'de, D: Deserializer<'de>>(
     deserializer: D,
 ) -> Result<Option<WindowId>, D::Error> {
@@ -139,7 +154,7 @@ fn deserialize_window_id_maybe_zero<'de, D: Deserializer<'de>>(
     })
 }
 
-fn deserialize_space_label<'de, D: Deserializer<'de>>(
+fn deserialize_label<'de, D: Deserializer<'de>>(
     deserializer: D,
 ) -> Result<Option<String>, D::Error> {
     let label = String::deserialize(deserializer)?;
@@ -155,7 +170,7 @@ pub struct Space {
 
     pub index: SpaceIndex,
 
-    #[serde(deserialize_with = "deserialize_space_label")]
+    #[serde(deserialize_with = "deserialize_label")]
     pub label: Option<String>,
 
     pub r#type: SpaceType,
@@ -189,11 +204,28 @@ pub struct Window {
     #[serde(rename = "space")]
     pub space_index: SpaceIndex,
 
+    /// Position of the window in its stack, starting from 1. 0 when the window is not stacked.
+    pub stack_index: u32,
+
     pub has_focus: bool,
     pub is_visible: bool,
+    pub is_minimized: bool,
     pub is_hidden: bool,
     pub is_floating: bool,
     pub is_sticky: bool,
+    pub is_native_fullscreen: bool,
+    pub has_parent_zoom: bool,
+    pub has_fullscreen_zoom: bool,
+}
+
+impl Window {
+    /// Whether both windows are stacked together in the same stack.
+    pub fn is_stacked_with(&self, other: &Window) -> bool {
+        self.stack_index > 0
+            && other.stack_index > 0
+            && self.space_index == other.space_index
+            && self.frame == other.frame
+    }
 }
 
 impl AsRef<Frame> for Window {
@@ -202,6 +234,65 @@ impl AsRef<Frame> for Window {
     }
 }
 
+/// Yabai objects with typical values, to be adjusted in tests.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::*;
+
+    /// An unlabeled display without spaces.
+    pub(crate) fn display(index: u32, frame: Frame) -> Display {
+        Display {
+            id: DisplayId(index),
+            uuid: format!("display-{index}"),
+            index: DisplayIndex(index),
+            label: None,
+            frame,
+            spaces: Vec::new(),
+        }
+    }
+
+    /// A visible, tiled window on the first space of the first display.
+    pub(crate) fn window(id: u32, frame: Frame) -> Window {
+        Window {
+            id: WindowId(id),
+            pid: 1,
+            app: "App".to_owned(),
+            title: format!("Window {id}"),
+            frame,
+            display_index: DisplayIndex(1),
+            space_index: SpaceIndex(1),
+            stack_index: 0,
+            has_focus: false,
+            is_visible: true,
+            is_minimized: false,
+            is_hidden: false,
+            is_floating: false,
+            is_sticky: false,
+            is_native_fullscreen: false,
+            has_parent_zoom: false,
+            has_fullscreen_zoom: false,
+        }
+    }
+
+    /// An invisible, empty space on the first display.
+    pub(crate) fn space(index: u32, label: Option<&str>) -> Space {
+        Space {
+            id: SpaceId(index),
+            uuid: format!("space-{index}"),
+            index: SpaceIndex(index),
+            label: label.map(ToOwned::to_owned),
+            r#type: SpaceType::BSP,
+            display_index: DisplayIndex(1),
+            windows: Vec::new(),
+            first_window: None,
+            last_window: None,
+            has_focus: false,
+            is_visible: false,
+            is_native_fullscreen: false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Frame;